@@ -0,0 +1,104 @@
+use std::fmt;
+
+/// The result of solving one part of a day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Integer(i64),
+    BigInteger(i128),
+    Text(String),
+    /// Rows of a picture, e.g. letters drawn on a screen.
+    #[allow(dead_code)] // no implemented day draws its answer yet
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// Renders the answer on a single line, joining the rows of a grid with `/`.
+    pub fn inline(&self) -> String {
+        match self {
+            Answer::Grid(rows) => rows.join("/"),
+            _ => self.to_string(),
+        }
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Grid(rows) if rows.len() > 1)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::BigInteger(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Answer {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Answer {
+        Answer::Integer(n)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Answer {
+        match i64::try_from(n) {
+            Ok(n) => Answer::Integer(n),
+            Err(_) => Answer::BigInteger(n as i128),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::from(n as u64)
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(n: i128) -> Answer {
+        Answer::BigInteger(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::Answer;
+
+    #[test]
+    fn it_keeps_small_numbers_as_integers() {
+        assert_eq!(Answer::from(42u32), Answer::Integer(42));
+        assert_eq!(Answer::from(42usize), Answer::Integer(42));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInteger(u64::MAX as i128));
+    }
+
+    #[test]
+    fn it_renders_grids_on_multiple_lines_or_inline() {
+        let grid = Answer::Grid(vec!["#..".to_string(), ".#.".to_string()]);
+
+        assert_eq!(grid.to_string(), "#..\n.#.");
+        assert_eq!(grid.inline(), "#../.#.");
+        assert!(grid.is_multiline());
+        assert!(!Answer::from("CMZ").is_multiline());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::Part;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::str::FromStr;
+
+/// Known-correct answers, stored one per line as tab-separated `day part input answer`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    entries: BTreeMap<(u8, Part, String), String>,
+}
+
+/// How a run compares with the stored answer for its day, part and input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Check {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl Answers {
+    /// Loads the answers stored at `path`; a missing file holds no answers yet.
+    pub fn load(path: &str) -> Result<Answers, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Answers::parse(&content).map_err(|e| format!("{}: {}", path, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("cannot read {}: {}", path, e)),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+        for (index, line) in content.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.splitn(4, '\t').collect();
+            let [day, part, input, answer] = fields[..] else {
+                return Err(format!(
+                    "line {}: expected day, part, input and answer separated by tabs",
+                    index + 1
+                ));
+            };
+            let day = day
+                .parse::<u8>()
+                .map_err(|_| format!("line {}: invalid day '{}'", index + 1, day))?;
+            let part = Part::from_str(part)
+                .map_err(|_| format!("line {}: invalid part '{}', use A or B", index + 1, part))?;
+            answers.insert(day, part, input, answer);
+        }
+        Ok(answers)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|e| format!("cannot write {}: {}", path, e))
+    }
+
+    pub fn insert(&mut self, day: u8, part: Part, input: &str, answer: &str) {
+        self.entries
+            .insert((day, part, input.to_string()), answer.to_string());
+    }
+
+    pub fn check(&self, day: u8, part: Part, input: &str, answer: &str) -> Check {
+        match self.entries.get(&(day, part, input.to_string())) {
+            None => Check::Missing,
+            Some(expected) if expected == answer => Check::Pass,
+            Some(expected) => Check::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "# day\tpart\tinput\tanswer")?;
+        for ((day, part, input), answer) in self.entries.iter() {
+            writeln!(f, "{}\t{:?}\t{}\t{}", day, part, input, answer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answers::{Answers, Check};
+    use crate::Part;
+
+    #[test]
+    fn it_reads_back_what_it_writes() {
+        let mut answers = Answers::default();
+        answers.insert(5, Part::A, "input/day5", "CMZ");
+        answers.insert(1, Part::B, "input/day1", "45000");
+
+        assert_eq!(Answers::parse(&answers.to_string()), Ok(answers));
+    }
+
+    #[test]
+    fn it_checks_an_answer_against_the_stored_one() {
+        let answers = Answers::parse("1\tA\tinput/day1\t24000\n").unwrap();
+
+        assert_eq!(
+            answers.check(1, Part::A, "input/day1", "24000"),
+            Check::Pass
+        );
+        assert_eq!(
+            answers.check(1, Part::A, "input/day1", "24001"),
+            Check::Fail {
+                expected: "24000".to_string()
+            }
+        );
+        assert_eq!(answers.check(1, Part::B, "input/day1", "1"), Check::Missing);
+        assert_eq!(answers.check(1, Part::A, "example", "1"), Check::Missing);
+    }
+
+    #[test]
+    fn it_rejects_malformed_lines() {
+        assert!(Answers::parse("1 A input/day1 24000").is_err());
+        assert!(Answers::parse("1\tC\tinput/day1\t24000").is_err());
+    }
+}
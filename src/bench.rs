@@ -0,0 +1,197 @@
+use crate::solvers::{Config, Timings};
+use crate::{read_lines, solvers, Part};
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// Summary of the timed runs of one phase.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from(durations: &[Duration]) -> Option<Stats> {
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let percentile = |p: usize| sorted[(sorted.len() * p).div_ceil(100).max(1) - 1];
+        Some(Stats {
+            min: *sorted.first()?,
+            median: percentile(50),
+            p95: percentile(95),
+        })
+    }
+}
+
+/// Median duration of every phase recorded by the last benchmark, stored one per line as
+/// tab-separated `day phase input nanoseconds`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: BTreeMap<(u8, String, String), u128>,
+}
+
+impl Baseline {
+    /// Loads the baseline stored at `path`; a missing file is an empty baseline.
+    pub fn load(path: &str) -> Result<Baseline, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Baseline::default()),
+            Err(e) => return Err(format!("cannot read {}: {}", path, e)),
+        };
+        let mut baseline = Baseline::default();
+        for (index, line) in content.lines().enumerate() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let parsed = match fields[..] {
+                [day, phase, input, nanos] => day
+                    .parse::<u8>()
+                    .ok()
+                    .zip(nanos.parse::<u128>().ok())
+                    .map(|(day, nanos)| ((day, phase.to_string(), input.to_string()), nanos)),
+                _ => None,
+            };
+            let (key, nanos) =
+                parsed.ok_or_else(|| format!("{}: line {} is malformed", path, index + 1))?;
+            baseline.medians.insert(key, nanos);
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let content: String = self
+            .medians
+            .iter()
+            .map(|((day, phase, input), nanos)| {
+                format!("{}\t{}\t{}\t{}\n", day, phase, input, nanos)
+            })
+            .collect();
+        fs::write(path, content).map_err(|e| format!("cannot write {}: {}", path, e))
+    }
+
+    fn get(&self, day: u8, phase: &str, input: &str) -> Option<Duration> {
+        self.medians
+            .get(&(day, phase.to_string(), input.to_string()))
+            .map(|nanos| Duration::from_nanos(*nanos as u64))
+    }
+
+    fn insert(&mut self, day: u8, phase: &str, input: &str, median: Duration) {
+        self.medians.insert(
+            (day, phase.to_string(), input.to_string()),
+            median.as_nanos(),
+        );
+    }
+}
+
+/// Benchmarks every day, compares the medians with the baseline at `baseline_path` and then
+/// replaces it with this run. Returns false if any day failed.
+pub fn run(
+    days: &[u8],
+    parts: &[Part],
+    input: Option<&str>,
+    warmup: usize,
+    iterations: usize,
+    baseline_path: &str,
+    config: &Config,
+) -> Result<bool, String> {
+    let previous = Baseline::load(baseline_path)?;
+    let mut current = Baseline::default();
+    let mut ok = true;
+
+    println!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  VS BASELINE",
+        "DAY", "PHASE", "MIN", "MEDIAN", "P95"
+    );
+    for &day in days {
+        let Some(puzzle) = solvers::find(day, config) else {
+            continue;
+        };
+        let path = input.map_or_else(|| format!("input/day{}", day), String::from);
+        let timings = read_lines(&path).and_then(|lines| {
+            puzzle
+                .bench(&lines, parts, warmup, iterations)
+                .map_err(|e| e.in_file(&path).to_string())
+        });
+        let Timings {
+            parse,
+            part_a,
+            part_b,
+        } = match timings {
+            Ok(timings) => timings,
+            Err(e) => {
+                eprintln!("{} day {}: {}", "ERROR".red(), day, e);
+                ok = false;
+                continue;
+            }
+        };
+        for (phase, durations) in [("parse", parse), ("part A", part_a), ("part B", part_b)] {
+            let Some(stats) = Stats::from(&durations) else {
+                continue;
+            };
+            let comparison = match previous.get(day, phase, &path) {
+                None => "-".normal(),
+                Some(before) => {
+                    let change = (stats.median.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+                    let text = format!("{:+.1}% (was {:.1?})", change, before);
+                    if change <= -5.0 {
+                        text.green()
+                    } else if change >= 5.0 {
+                        text.red()
+                    } else {
+                        text.normal()
+                    }
+                }
+            };
+            println!(
+                "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {}",
+                day,
+                phase,
+                format!("{:.1?}", stats.min),
+                format!("{:.1?}", stats.median),
+                format!("{:.1?}", stats.p95),
+                comparison
+            );
+            current.insert(day, phase, &path, stats.median);
+        }
+    }
+
+    let mut baseline = previous;
+    baseline.medians.append(&mut current.medians);
+    baseline.save(baseline_path)?;
+    Ok(ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::{Baseline, Stats};
+    use std::time::Duration;
+
+    #[test]
+    fn it_summarises_durations() {
+        let durations: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+
+        assert_eq!(
+            Stats::from(&durations),
+            Some(Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(10),
+                p95: Duration::from_millis(19),
+            })
+        );
+        assert_eq!(Stats::from(&[]), None);
+    }
+
+    #[test]
+    fn it_keeps_the_median_of_each_phase() {
+        let mut baseline = Baseline::default();
+        baseline.insert(6, "part B", "input/day6", Duration::from_micros(250));
+
+        assert_eq!(
+            baseline.get(6, "part B", "input/day6"),
+            Some(Duration::from_micros(250))
+        );
+        assert_eq!(baseline.get(6, "part A", "input/day6"), None);
+    }
+}
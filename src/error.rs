@@ -0,0 +1,142 @@
+use std::fmt;
+
+/// Malformed input, located precisely enough to point a caret at the offending text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    /// 1-based line number, 0 while the error is not attached to a line yet.
+    pub line: usize,
+    /// 1-based column of the offending text inside `text`.
+    pub column: usize,
+    pub width: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error about `token`, which is expected to be a slice of `text`.
+    pub fn at(text: &str, token: &str, message: impl Into<String>) -> ParseError {
+        let start = text.as_ptr() as usize;
+        let offset = match (token.as_ptr() as usize).checked_sub(start) {
+            Some(offset) if offset + token.len() <= text.len() => offset,
+            _ => text.find(token).unwrap_or(0),
+        };
+        ParseError {
+            file: None,
+            line: 0,
+            column: text[..offset].chars().count() + 1,
+            width: token.chars().count().max(1),
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// An error about the whole of `text`.
+    pub fn line(text: &str, message: impl Into<String>) -> ParseError {
+        ParseError::at(text, text, message)
+    }
+
+    /// Attaches the error to the line at `index` (0-based) of the input.
+    pub fn on_line(mut self, index: usize) -> ParseError {
+        self.line = index + 1;
+        self
+    }
+
+    /// Re-anchors an error raised on a fragment of `line`, the fragment starting `offset` chars in.
+    pub fn within(mut self, line: &str, offset: usize) -> ParseError {
+        self.column += offset;
+        self.text = line.to_string();
+        self
+    }
+
+    pub fn in_file(mut self, file: &str) -> ParseError {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let location = match &self.file {
+            Some(file) => format!("{}:{}:{}", file, self.line, self.column),
+            None => format!("line {}, column {}", self.line, self.column),
+        };
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{}--> {}", gutter, location)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width)
+        )
+    }
+}
+
+/// Why a part could not produce an answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    Parse(ParseError),
+    /// The input is well-formed but the puzzle has no answer for it.
+    NoAnswer(String),
+}
+
+impl SolveError {
+    pub fn no_answer(message: impl Into<String>) -> SolveError {
+        SolveError::NoAnswer(message.into())
+    }
+
+    pub fn in_file(self, file: &str) -> SolveError {
+        match self {
+            SolveError::Parse(error) => SolveError::Parse(error.in_file(file)),
+            error => error,
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(error: ParseError) -> SolveError {
+        SolveError::Parse(error)
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Parse(error) => write!(f, "{}", error),
+            SolveError::NoAnswer(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ParseError;
+
+    #[test]
+    fn it_locates_a_token_inside_its_line() {
+        let line = "move 1 from x to 3";
+        let error = ParseError::at(line, &line[12..13], "invalid stack").on_line(4);
+
+        assert_eq!(error.line, 5);
+        assert_eq!(error.column, 13);
+        assert_eq!(error.width, 1);
+    }
+
+    #[test]
+    fn it_points_a_caret_at_the_offending_text() {
+        let line = "2-4,6-y";
+        let error = ParseError::at("6-y", "y", "invalid section")
+            .within(line, 4)
+            .on_line(1)
+            .in_file("input/day4");
+
+        assert_eq!(
+            error.to_string(),
+            "invalid section\n --> input/day4:2:7\n  |\n2 | 2-4,6-y\n  |       ^"
+        );
+    }
+}
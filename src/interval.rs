@@ -0,0 +1,259 @@
+use std::fmt;
+
+/// A non-empty range of integers, both ends included, like the `2-4` of a section assignment.
+///
+/// Only the ends are stored, so every operation costs the same however long the range is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    /// The interval from `start` to `end`, or `None` if it would end before it starts.
+    pub fn new(start: i64, end: i64) -> Option<Interval> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    /// Number of integers in the interval.
+    pub fn len(&self) -> u64 {
+        self.start.abs_diff(self.end) + 1
+    }
+
+    /// Whether `other` lies completely inside this interval.
+    pub fn contains(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The integers in both intervals, if there are any.
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// The integers covered by any of a number of intervals, kept as sorted disjoint intervals.
+#[derive(Debug, Default, PartialEq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn insert(&mut self, interval: Interval) {
+        let mut merged = interval;
+        // intervals that overlap or touch the new one are folded into it
+        self.intervals.retain(|existing| {
+            let apart = existing.end.saturating_add(1) < merged.start
+                || merged.end.saturating_add(1) < existing.start;
+            if !apart {
+                merged = Interval {
+                    start: existing.start.min(merged.start),
+                    end: existing.end.max(merged.end),
+                };
+            }
+            apart
+        });
+        let position = self.intervals.partition_point(|i| i.start < merged.start);
+        self.intervals.insert(position, merged);
+    }
+
+    /// The covered intervals, in order.
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// The intervals left uncovered between the first and the last covered integer.
+    pub fn gaps(&self) -> Vec<Interval> {
+        self.intervals
+            .windows(2)
+            .filter_map(|pair| Interval::new(pair[0].end + 1, pair[1].start - 1))
+            .collect()
+    }
+
+    /// Number of covered integers.
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = Interval>>(intervals: T) -> IntervalSet {
+        let mut set = IntervalSet::default();
+        for interval in intervals {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+/// How many of a number of intervals cover each integer.
+///
+/// The count only changes where an interval starts or just after one ends, so it is kept as the
+/// list of those changes rather than per integer.
+#[derive(Debug, PartialEq)]
+pub struct Coverage {
+    /// From each point on, up to the next one, this many intervals cover the integers.
+    steps: Vec<(i64, usize)>,
+}
+
+impl Coverage {
+    pub fn new<'a>(intervals: impl IntoIterator<Item = &'a Interval>) -> Coverage {
+        let mut changes: Vec<(i64, isize)> = Vec::new();
+        for interval in intervals {
+            changes.push((interval.start, 1));
+            changes.push((interval.end.saturating_add(1), -1));
+        }
+        changes.sort();
+
+        let mut steps: Vec<(i64, usize)> = Vec::new();
+        let mut count: usize = 0;
+        for (point, change) in changes {
+            count = count.saturating_add_signed(change);
+            match steps.last_mut() {
+                Some(last) if last.0 == point => last.1 = count,
+                _ => steps.push((point, count)),
+            }
+        }
+        steps.dedup_by(|next, previous| next.1 == previous.1);
+        Coverage { steps }
+    }
+
+    /// Number of intervals covering `point`.
+    pub fn count_at(&self, point: i64) -> usize {
+        match self.steps.partition_point(|(start, _)| *start <= point) {
+            0 => 0,
+            index => self.steps[index - 1].1,
+        }
+    }
+
+    /// Every interval covered by a constant, non-zero number of the intervals, with that number.
+    pub fn counts(&self) -> Vec<(Interval, usize)> {
+        self.steps
+            .windows(2)
+            .filter(|pair| pair[0].1 > 0)
+            .filter_map(|pair| Some((Interval::new(pair[0].0, pair[1].0 - 1)?, pair[0].1)))
+            .collect()
+    }
+
+    /// The largest number of intervals covering any integer, and where they do.
+    pub fn max_overlap(&self) -> Option<(usize, Vec<Interval>)> {
+        let counts = self.counts();
+        let max = counts.iter().map(|(_, count)| *count).max()?;
+        let intervals = counts
+            .into_iter()
+            .filter(|(_, count)| *count == max)
+            .map(|(interval, _)| interval)
+            .collect();
+        Some((max, intervals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interval::{Coverage, Interval, IntervalSet};
+
+    fn interval(start: i64, end: i64) -> Interval {
+        Interval::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn it_refuses_to_end_before_it_starts() {
+        assert_eq!(Interval::new(4, 2), None);
+        assert_eq!(interval(2, 2).len(), 1);
+        assert_eq!(interval(1, 4_000_000_000).len(), 4_000_000_000);
+        assert_eq!(interval(2, 4).to_string(), "2-4");
+    }
+
+    #[test]
+    fn it_returns_if_an_interval_contains_another() {
+        assert!(interval(2, 2).contains(&interval(2, 2)));
+        assert!(interval(2, 4).contains(&interval(3, 3)));
+        assert!(interval(2, 4).contains(&interval(2, 3)));
+        assert!(interval(2, 4).contains(&interval(3, 4)));
+
+        assert!(!interval(3, 3).contains(&interval(2, 4)));
+        assert!(!interval(2, 4).contains(&interval(1, 2)));
+        assert!(!interval(2, 4).contains(&interval(4, 5)));
+    }
+
+    #[test]
+    fn it_returns_if_two_intervals_overlap() {
+        assert!(interval(2, 4).overlaps(&interval(3, 3)));
+        assert!(interval(2, 4).overlaps(&interval(1, 2)));
+        assert!(interval(2, 4).overlaps(&interval(4, 5)));
+        assert!(interval(4, 5).overlaps(&interval(2, 4)));
+
+        assert!(!interval(2, 2).overlaps(&interval(3, 3)));
+        assert!(!interval(6, 8).overlaps(&interval(2, 4)));
+    }
+
+    #[test]
+    fn it_intersects_two_intervals() {
+        assert_eq!(
+            interval(2, 6).intersection(&interval(4, 8)),
+            Some(interval(4, 6))
+        );
+        assert_eq!(
+            interval(2, 8).intersection(&interval(3, 7)),
+            Some(interval(3, 7))
+        );
+        assert_eq!(interval(2, 4).intersection(&interval(6, 8)), None);
+    }
+
+    #[test]
+    fn it_merges_overlapping_and_touching_intervals() {
+        let set: IntervalSet = [
+            interval(6, 8),
+            interval(2, 4),
+            interval(12, 14),
+            interval(5, 5),
+            interval(20, 30),
+            interval(13, 21),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(set.intervals(), [interval(2, 8), interval(12, 30)]);
+        assert_eq!(set.gaps(), vec![interval(9, 11)]);
+        assert_eq!(set.len(), 26);
+    }
+
+    #[test]
+    fn it_counts_the_intervals_covering_each_integer() {
+        let intervals = [
+            interval(2, 4),
+            interval(3, 6),
+            interval(4, 4),
+            interval(8, 9),
+        ];
+        let coverage = Coverage::new(&intervals);
+
+        assert_eq!(coverage.count_at(1), 0);
+        assert_eq!(coverage.count_at(3), 2);
+        assert_eq!(coverage.count_at(4), 3);
+        assert_eq!(coverage.count_at(7), 0);
+        assert_eq!(coverage.count_at(9), 1);
+        assert_eq!(coverage.count_at(10), 0);
+        assert_eq!(
+            coverage.counts(),
+            vec![
+                (interval(2, 2), 1),
+                (interval(3, 3), 2),
+                (interval(4, 4), 3),
+                (interval(5, 6), 1),
+                (interval(8, 9), 1)
+            ]
+        );
+        assert_eq!(coverage.max_overlap(), Some((3, vec![interval(4, 4)])));
+        assert_eq!(Coverage::new(&[]).max_overlap(), None);
+    }
+}
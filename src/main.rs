@@ -1,128 +1,482 @@
-use clap::Parser;
+use crate::answer::Answer;
+use crate::answers::{Answers, Check};
+use crate::error::SolveError;
+use crate::output::Format;
+use crate::solvers::day2::Rules;
+use crate::solvers::day7::{Disk, Show, SortBy, TreeView};
+use crate::solvers::{Config, Puzzle};
+use clap::{Parser, ValueEnum};
 use colored::Colorize;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::cell::OnceCell;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::panic;
+use std::process;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
+mod answer;
+mod answers;
+mod bench;
+mod error;
+mod interval;
+mod output;
 mod solvers;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Which day to solve
-    #[arg(short, long, default_value_t = 1)]
-    day: u8,
+    /// Which day to solve, or an inclusive range of days like 3..7
+    #[arg(short, long, default_value = "1")]
+    day: Days,
+
+    /// Which part to solve, both when omitted
+    #[arg(short, long, value_enum)]
+    part: Option<Part>,
+
+    /// Solve every implemented day
+    #[arg(short, long)]
+    all: bool,
+
+    /// Read the puzzle input from this file instead of input/dayN, or from stdin with -
+    #[arg(short, long)]
+    input: Option<String>,
 
-    /// Which part (A or B) to solve
+    /// Compare every answer with the one stored in the answers file
     #[arg(short, long)]
-    part: String,
+    check: bool,
+
+    /// Store every answer in the answers file as the known-correct one
+    #[arg(long, conflicts_with = "check")]
+    record: bool,
+
+    /// File of known-correct answers used by --check and --record
+    #[arg(long, default_value = "answers.tsv")]
+    answers: String,
+
+    /// Time the parsing and each part instead of printing the answers
+    #[arg(short, long)]
+    bench: bool,
+
+    /// Number of timed runs of every phase with --bench
+    #[arg(long, default_value_t = 100)]
+    iterations: usize,
+
+    /// Number of untimed runs before the timed ones with --bench
+    #[arg(long, default_value_t = 10)]
+    warmup: usize,
+
+    /// File keeping the medians of the last --bench, to compare the next one with
+    #[arg(long, default_value = "bench-baseline.tsv")]
+    baseline: String,
+
+    /// How to print the results
+    #[arg(short, long, value_enum, default_value_t = Format::Plain)]
+    format: Format,
+
+    /// Print a detailed account of each day's input instead of the answers
+    #[arg(short, long, conflicts_with = "bench")]
+    report: bool,
+
+    /// Print how each part reaches its answer, step by step, instead of the answers
+    #[arg(short, long, conflicts_with_all = ["bench", "report"])]
+    explain: bool,
+
+    /// Number of elves whose calories day 1 part B adds up, 3 when omitted
+    #[arg(long)]
+    top: Option<NonZeroUsize>,
+
+    /// File of rules for the day 2 game, rock-paper-scissors when omitted
+    #[arg(long)]
+    rules: Option<String>,
+
+    /// Number of elves in a day 3 group, 3 when omitted
+    #[arg(long)]
+    group_size: Option<NonZeroUsize>,
+
+    /// Section whose number of elves the day 4 report gives, can be repeated
+    #[arg(long = "section")]
+    sections: Vec<i64>,
+
+    /// Length of the day 6 marker for both parts, instead of 4 and 14
+    #[arg(long)]
+    window: Option<NonZeroUsize>,
+
+    /// What the day 7 report shows
+    #[arg(long, value_enum, default_value_t = Show::Tree)]
+    show: Show,
+
+    /// Capacity in bytes of the day 7 disk
+    #[arg(long, default_value_t = Disk::default().capacity)]
+    disk_size: u64,
+
+    /// Free bytes the day 7 update needs
+    #[arg(long, default_value_t = Disk::default().needed)]
+    space_needed: u64,
+
+    /// Deepest directory level drawn by the day 7 report
+    #[arg(long)]
+    depth: Option<usize>,
+
+    /// Leave entries smaller than this many bytes out of the day 7 report
+    #[arg(long, default_value_t = 0)]
+    min_size: u64,
+
+    /// Order of the entries of each directory in the day 7 report
+    #[arg(long, value_enum, default_value_t = SortBy::Listing)]
+    sort: SortBy,
+}
+
+#[derive(Debug, Clone)]
+struct Days(RangeInclusive<u8>);
+
+impl FromStr for Days {
+    type Err = String;
+    fn from_str(input: &str) -> Result<Days, Self::Err> {
+        let parse = |s: &str| {
+            s.trim()
+                .parse::<u8>()
+                .map_err(|_| format!("invalid day '{}'", s))
+        };
+        let range = match input.split_once("..") {
+            None => parse(input)?..=parse(input)?,
+            Some((start, end)) => parse(start)?..=parse(end.trim_start_matches('='))?,
+        };
+        if range.is_empty() {
+            return Err(format!("empty range of days '{}'", input));
+        }
+        Ok(Days(range))
+    }
 }
 
 fn main() {
     let args: Args = Args::parse();
-    let part = Part::from_str(args.part.as_str()).expect("invalid argument 'part', use A or B");
-    println!("Solve day {} part {:?}!", args.day, part);
-    let result = match args.day {
-        1 => {
-            format!(
-                "{}: {}",
-                format!("SOLUTION").green(),
-                solvers::day1::solve(part, read_lines("input/day1"))
-            )
-        }
-        2 => {
-            format!(
-                "{}: {}",
-                format!("SOLUTION").green(),
-                solvers::day2::solve(part, read_lines("input/day2"))
-            )
-        }
-        3 => {
-            format!(
-                "{}: {}",
-                format!("SOLUTION").green(),
-                solvers::day3::solve(part, read_lines("input/day3"))
-            )
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![Part::A, Part::B],
+    };
+
+    let single_day = !args.all && args.day.0.start() == args.day.0.end();
+    if args.input.is_some() && !single_day {
+        eprintln!("{}: --input needs a single --day", "ERROR".red());
+        process::exit(2);
+    }
+
+    let rules = match &args.rules {
+        Some(path) => Rules::load(path).unwrap_or_else(|e| {
+            eprintln!("{}: {}", "ERROR".red(), e);
+            process::exit(2);
+        }),
+        None => Rules::default(),
+    };
+    let config = Config {
+        top: args.top,
+        rules,
+        group_size: args.group_size,
+        sections: args.sections.clone(),
+        window: args.window,
+        disk: Disk {
+            capacity: args.disk_size,
+            needed: args.space_needed,
+        },
+        show: args.show,
+        tree: TreeView {
+            max_depth: args.depth,
+            min_size: args.min_size,
+            sort: args.sort,
+        },
+    };
+
+    let days: Vec<u8> = if args.all {
+        solvers::registry(&config)
+            .into_iter()
+            .map(|(day, _)| day)
+            .collect()
+    } else {
+        args.day.0.clone().collect()
+    };
+
+    if args.bench {
+        let bench = bench::run(
+            &days,
+            &parts,
+            args.input.as_deref(),
+            args.warmup,
+            args.iterations,
+            &args.baseline,
+            &config,
+        );
+        match bench {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}: {}", "ERROR".red(), e);
+                process::exit(2);
+            }
         }
-        4 => {
-            format!(
-                "{}: {}",
-                format!("SOLUTION").green(),
-                solvers::day4::solve(part, read_lines("input/day4"))
-            )
+    }
+
+    if args.report || args.explain {
+        let failed = days
+            .iter()
+            .filter(|&&day| {
+                !describe_day(day, &parts, args.explain, args.input.as_deref(), &config)
+            })
+            .count();
+        process::exit(if failed > 0 { 1 } else { 0 });
+    }
+
+    let mut runs: Vec<Run> = days
+        .into_iter()
+        .flat_map(|day| solve_day(day, &parts, args.input.as_deref(), &config))
+        .collect();
+
+    if args.check || args.record {
+        let mut answers = Answers::load(&args.answers).unwrap_or_else(|e| {
+            eprintln!("{}: {}", "ERROR".red(), e);
+            process::exit(2);
+        });
+        if args.check {
+            for run in runs.iter_mut() {
+                run.check = run
+                    .answer
+                    .as_ref()
+                    .map(|answer| answers.check(run.day, run.part, &run.input, &answer.inline()));
+            }
+        } else {
+            record(&mut answers, &runs, &args.answers);
         }
-        5 => {
-            format!(
-                "{}: {}",
-                format!("SOLUTION").green(),
-                /*
-                    [C]         [Q]         [V]
-                    [D]         [D] [S]     [M] [Z]
-                    [G]     [P] [W] [M]     [C] [G]
-                    [F]     [Z] [C] [D] [P] [S] [W]
-                [P] [L]     [C] [V] [W] [W] [H] [L]
-                [G] [B] [V] [R] [L] [N] [G] [P] [F]
-                [R] [T] [S] [S] [S] [T] [D] [L] [P]
-                [N] [J] [M] [L] [P] [C] [H] [Z] [R]
-                 1   2   3   4   5   6   7   8   9
-                */
-                solvers::day5::solve(
-                    part,
-                    vec![
-                        vec!['N', 'R', 'G', 'P'],
-                        vec!['J', 'T', 'B', 'L', 'F', 'G', 'D', 'C'],
-                        vec!['M', 'S', 'V'],
-                        vec!['L', 'S', 'R', 'C', 'Z', 'P'],
-                        vec!['P', 'S', 'L', 'V', 'C', 'W', 'D', 'Q'],
-                        vec!['C', 'T', 'N', 'W', 'D', 'M', 'S'],
-                        vec!['H', 'D', 'G', 'W', 'P'],
-                        vec!['Z', 'L', 'P', 'H', 'S', 'C', 'M', 'V'],
-                        vec!['R', 'P', 'F', 'L', 'W', 'G', 'Z'],
-                    ],
-                    read_lines("input/day5")
-                )
-            )
+    }
+
+    output::print(&runs, args.format, single_day && parts.len() == 1);
+
+    if runs
+        .iter()
+        .any(|r| r.is_failure() || matches!(r.check, Some(Check::Fail { .. })))
+    {
+        process::exit(1);
+    }
+}
+
+fn record(answers: &mut Answers, runs: &[Run], path: &str) {
+    let mut recorded = 0;
+    for run in runs {
+        match &run.answer {
+            Some(_) if run.input == STDIN => {
+                eprintln!(
+                    "{}: answers read from stdin cannot be recorded",
+                    "WARNING".yellow()
+                );
+            }
+            Some(answer) => {
+                answers.insert(run.day, run.part, &run.input, &answer.inline());
+                recorded += 1;
+            }
+            None => {}
         }
-        6 => {
-            format!(
-                "{}: {}",
-                format!("SOLUTION").green(),
-                solvers::day6::solve(part, read_lines("input/day6").pop().unwrap())
-            )
+    }
+    if let Err(e) = answers.save(path) {
+        eprintln!("{}: {}", "ERROR".red(), e);
+        process::exit(2);
+    }
+    eprintln!("Recorded {} answers in {}", recorded, path);
+}
+
+struct Run {
+    day: u8,
+    part: Part,
+    input: String,
+    answer: Option<Answer>,
+    status: Status,
+    check: Option<Check>,
+    /// Rendered problems found in the input that did not stop the solver.
+    warnings: Vec<String>,
+    /// Time spent parsing the input and solving the part.
+    elapsed: Duration,
+}
+
+impl Run {
+    fn is_failure(&self) -> bool {
+        matches!(self.status, Status::Failed(_))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Status {
+    Ok,
+    /// The rendered error, or the message of a panicking solver.
+    Failed(String),
+    NotImplemented,
+}
+
+/// Solves the given parts of one day, reading its input only once so that stdin can be used.
+/// Errors and panics of the solver become failed runs.
+fn solve_day(day: u8, parts: &[Part], input: Option<&str>, config: &Config) -> Vec<Run> {
+    let path = input.map_or_else(|| format!("input/day{}", day), String::from);
+    let puzzle = solvers::find(day, config);
+    // only read once a part needs the lines, days streaming from the file never do
+    let lines = OnceCell::new();
+
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let (answer, status, warnings) = match &puzzle {
+                Some(puzzle) => {
+                    let name = display_name(&path);
+                    let solved = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                        if let Some(streamed) = stream(puzzle.as_ref(), &path, part) {
+                            return Ok((streamed?, Vec::new()));
+                        }
+                        let lines = lines.get_or_init(|| read_lines(&path));
+                        let outcome = puzzle.run(part, lines.clone()?);
+                        let warnings = outcome
+                            .warnings
+                            .into_iter()
+                            .map(|w| w.in_file(name).to_string())
+                            .collect();
+                        Ok((outcome.answer, warnings))
+                    }));
+                    match solved {
+                        Ok(Ok((Ok(answer), warnings))) => (Some(answer), Status::Ok, warnings),
+                        Ok(Ok((Err(error), warnings))) => (
+                            None,
+                            Status::Failed(error.in_file(name).to_string()),
+                            warnings,
+                        ),
+                        Ok(Err(error)) => (None, Status::Failed(error), Vec::new()),
+                        Err(_) => (
+                            None,
+                            Status::Failed("the solver panicked".to_string()),
+                            Vec::new(),
+                        ),
+                    }
+                }
+                None => (None, Status::NotImplemented, Vec::new()),
+            };
+            Run {
+                day,
+                part,
+                input: path.clone(),
+                answer,
+                status,
+                check: None,
+                warnings,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// Solves `part` straight from the input file if the day can, without reading it as lines.
+/// Anything but a regular file, like stdin or a pipe, is left to the lines, as it cannot be read
+/// again for the next part.
+fn stream(
+    puzzle: &dyn Puzzle,
+    path: &str,
+    part: Part,
+) -> Option<Result<Result<Answer, SolveError>, String>> {
+    if !puzzle.streams() || path == STDIN || !fs::metadata(path).is_ok_and(|m| m.is_file()) {
+        return None;
+    }
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => return Some(Err(format!("cannot open {}: {}", path, e))),
+    };
+    let streamed = puzzle.stream(&mut file, part)?;
+    Some(streamed.map_err(|e| format!("cannot read {}: {}", path, e)))
+}
+
+/// Prints the report of one day, or with `explain` how each of `parts` is solved. Returns false
+/// if any of them could not be made.
+fn describe_day(
+    day: u8,
+    parts: &[Part],
+    explain: bool,
+    input: Option<&str>,
+    config: &Config,
+) -> bool {
+    let Some(puzzle) = solvers::find(day, config) else {
+        eprintln!("{}: Solver for day {} not implemented", "ERROR".red(), day);
+        return false;
+    };
+    let path = input.map_or_else(|| format!("input/day{}", day), String::from);
+    let lines = match read_lines(&path) {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("{} day {}: {}", "ERROR".red(), day, e);
+            return false;
         }
-        7 => {
-            format!(
-                "{}: {}",
-                format!("SOLUTION").green(),
-                solvers::day7::solve(part, read_lines("input/day7"))
-            )
+    };
+    let accounts = if explain {
+        parts
+            .iter()
+            .map(|&part| {
+                let title = format!("Day {} part {:?}", day, part);
+                (title, "explanation", puzzle.explain(part, lines.clone()))
+            })
+            .collect()
+    } else {
+        vec![(format!("Day {}", day), "report", puzzle.report(lines))]
+    };
+
+    let mut ok = true;
+    for (title, kind, account) in accounts {
+        match account {
+            Ok(Some(text)) => {
+                println!("{}", title.bold());
+                print!("{}", text);
+            }
+            Ok(None) => eprintln!("{}: {} has no {}", "WARNING".yellow(), title, kind),
+            Err(e) => {
+                eprintln!(
+                    "{} {}: {}",
+                    "ERROR".red(),
+                    title,
+                    e.in_file(display_name(&path))
+                );
+                ok = false;
+            }
         }
-        _ => format!(
-            "{}: Solver for day {} not implemented",
-            format!("ERROR").red(),
-            args.day
-        ),
+    }
+    ok
+}
+
+/// Reads the puzzle input from `filename`, or from stdin when it is `-`.
+fn read_lines(filename: &str) -> Result<Vec<String>, String> {
+    let reader: Box<dyn BufRead> = if filename == STDIN {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(filename).map_err(|e| format!("cannot open {}: {}", filename, e))?;
+        Box::new(BufReader::new(file))
     };
-    println!("{}", result);
+
+    reader
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.map_err(|e| format!("cannot read line {} of {}: {}", index + 1, filename, e))
+        })
+        .collect()
 }
 
-fn read_lines(filename: &str) -> Vec<String> {
-    let file = File::open(filename).expect("File not found");
-    let reader = BufReader::new(file);
+const STDIN: &str = "-";
 
-    let mut lines = Vec::new();
-    for line in reader.lines() {
-        match line {
-            Ok(l) => lines.push(l),
-            Err(_) => println!("Unreadable line? 🤔"),
-        }
+fn display_name(path: &str) -> &str {
+    if path == STDIN {
+        "<stdin>"
+    } else {
+        path
     }
-    lines
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, ValueEnum)]
 pub enum Part {
+    #[value(name = "A")]
     A,
+    #[value(name = "B")]
     B,
 }
 impl FromStr for Part {
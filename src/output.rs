@@ -0,0 +1,314 @@
+use crate::answer::Answer;
+use crate::answers::Check;
+use crate::{Run, Status};
+use clap::ValueEnum;
+use colored::Colorize;
+
+/// How the results of a run are printed.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Coloured text for a terminal
+    Plain,
+    Json,
+    Csv,
+}
+
+pub fn print(runs: &[Run], format: Format, single: bool) {
+    match format {
+        Format::Plain if single => print_single(&runs[0]),
+        Format::Plain => print_summary(runs),
+        Format::Json => println!("{}", to_json(runs)),
+        Format::Csv => print!("{}", to_csv(runs)),
+    }
+    if format == Format::Plain {
+        print_warnings(runs);
+        print_errors(runs, single);
+        print_mismatches(runs);
+    }
+}
+
+fn print_single(run: &Run) {
+    println!("Solve day {} part {:?}!", run.day, run.part);
+    let result = match (&run.answer, &run.status) {
+        (Some(answer), _) => {
+            let separator = if answer.is_multiline() { "\n" } else { " " };
+            format!("{}:{}{}", "SOLUTION".green(), separator, answer)
+        }
+        (None, Status::Failed(error)) => format!("{}: {}", "ERROR".red(), error),
+        (None, _) => format!(
+            "{}: Solver for day {} not implemented",
+            "ERROR".red(),
+            run.day
+        ),
+    };
+    println!("{}", result);
+    match run.check {
+        Some(Check::Pass) => println!("{}: answer matches {}", "CHECK".green(), run.input),
+        Some(Check::Missing) => {
+            println!("{}: no stored answer for {}", "CHECK".yellow(), run.input)
+        }
+        _ => {}
+    }
+}
+
+/// Shows every answer that differs from the stored one as a small diff.
+fn print_mismatches(runs: &[Run]) {
+    for run in runs {
+        if let (Some(Check::Fail { expected }), Some(answer)) = (&run.check, &run.answer) {
+            eprintln!(
+                "\n{} day {} part {:?} on {}",
+                "MISMATCH".red(),
+                run.day,
+                run.part,
+                run.input
+            );
+            eprintln!("{}", format!("- {}", expected).green());
+            eprintln!("{}", format!("+ {}", answer.inline()).red());
+        }
+    }
+}
+
+fn print_summary(runs: &[Run]) {
+    let answers: Vec<String> = runs
+        .iter()
+        .map(|r| r.answer.as_ref().map(Answer::inline).unwrap_or_default())
+        .collect();
+    let width = answers
+        .iter()
+        .map(String::len)
+        .chain(Some("ANSWER".len()))
+        .max()
+        .unwrap();
+    let checked = runs.iter().any(|r| r.check.is_some());
+    let status_width = if checked { "not implemented".len() } else { 0 };
+    println!(
+        "{:>3}  {:<4}  {:<width$}  {:<status_width$}{}",
+        "DAY",
+        "PART",
+        "ANSWER",
+        "STATUS",
+        if checked { "  CHECK" } else { "" }
+    );
+    for (run, answer) in runs.iter().zip(answers) {
+        let status = match run.status {
+            Status::Ok => format!("{:<status_width$}", "ok").green(),
+            Status::Failed(_) => format!("{:<status_width$}", "failed").red(),
+            Status::NotImplemented => format!("{:<status_width$}", "not implemented").yellow(),
+        };
+        let check = match run.check {
+            Some(Check::Pass) => "  pass".green(),
+            Some(Check::Fail { .. }) => "  FAIL".red(),
+            Some(Check::Missing) => "  missing".yellow(),
+            None => "".normal(),
+        };
+        println!(
+            "{:>3}  {:<4}  {:<width$}  {}{}",
+            run.day,
+            format!("{:?}", run.part),
+            answer,
+            status,
+            check
+        );
+    }
+}
+
+/// Prints the warnings of every day once, even when both of its parts were solved.
+fn print_warnings(runs: &[Run]) {
+    let mut printed: Vec<(u8, &str)> = Vec::new();
+    for run in runs.iter() {
+        for warning in run.warnings.iter() {
+            if printed.contains(&(run.day, warning)) {
+                continue;
+            }
+            printed.push((run.day, warning));
+            eprintln!("\n{} day {}: {}", "WARNING".yellow(), run.day, warning);
+        }
+    }
+}
+
+fn print_errors(runs: &[Run], single: bool) {
+    if single {
+        return;
+    }
+    for run in runs.iter() {
+        if let Status::Failed(error) = &run.status {
+            eprintln!(
+                "\n{} day {} part {:?}: {}",
+                "ERROR".red(),
+                run.day,
+                run.part,
+                error
+            );
+        }
+    }
+}
+
+fn status_name(status: &Status) -> &'static str {
+    match status {
+        Status::Ok => "ok",
+        Status::Failed(_) => "failed",
+        Status::NotImplemented => "not implemented",
+    }
+}
+
+fn check_name(check: &Option<Check>) -> &'static str {
+    match check {
+        Some(Check::Pass) => "pass",
+        Some(Check::Fail { .. }) => "fail",
+        Some(Check::Missing) => "missing",
+        None => "",
+    }
+}
+
+fn elapsed_ms(run: &Run) -> String {
+    format!("{:.3}", run.elapsed.as_secs_f64() * 1000.0)
+}
+
+fn to_json(runs: &[Run]) -> String {
+    let objects: Vec<String> = runs
+        .iter()
+        .map(|run| {
+            let answer = match &run.answer {
+                None => "null".to_string(),
+                Some(Answer::Integer(n)) => n.to_string(),
+                // beyond 2^53 most JSON readers lose precision on numbers
+                Some(Answer::BigInteger(n)) => json_string(&n.to_string()),
+                Some(Answer::Text(text)) => json_string(text),
+                Some(Answer::Grid(rows)) => format!(
+                    "[{}]",
+                    rows.iter()
+                        .map(|r| json_string(r))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                ),
+            };
+            let error = match &run.status {
+                Status::Failed(error) => json_string(error),
+                _ => "null".to_string(),
+            };
+            let (check, expected) = match &run.check {
+                None => ("null".to_string(), "null".to_string()),
+                Some(Check::Fail { expected }) => {
+                    (json_string(check_name(&run.check)), json_string(expected))
+                }
+                Some(_) => (json_string(check_name(&run.check)), "null".to_string()),
+            };
+            let warnings: Vec<String> = run.warnings.iter().map(|w| json_string(w)).collect();
+            format!(
+                "{{\"day\":{},\"part\":\"{:?}\",\"answer\":{},\"elapsed_ms\":{},\"input\":{},\"status\":{},\"error\":{},\"check\":{},\"expected\":{},\"warnings\":[{}]}}",
+                run.day,
+                run.part,
+                answer,
+                elapsed_ms(run),
+                json_string(&run.input),
+                json_string(status_name(&run.status)),
+                error,
+                check,
+                expected,
+                warnings.join(",")
+            )
+        })
+        .collect();
+    format!("[{}]", objects.join(","))
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn to_csv(runs: &[Run]) -> String {
+    let mut csv = String::from("day,part,answer,elapsed_ms,input,status,check\n");
+    for run in runs {
+        let fields = [
+            run.day.to_string(),
+            format!("{:?}", run.part),
+            run.answer
+                .as_ref()
+                .map(Answer::to_string)
+                .unwrap_or_default(),
+            elapsed_ms(run),
+            run.input.clone(),
+            status_name(&run.status).to_string(),
+            check_name(&run.check).to_string(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::Answer;
+    use crate::answers::Check;
+    use crate::output::{csv_field, json_string, to_csv, to_json};
+    use crate::{Part, Run, Status};
+    use std::time::Duration;
+
+    fn run(answer: Answer, check: Option<Check>) -> Run {
+        Run {
+            day: 5,
+            part: Part::A,
+            input: "input/day5".to_string(),
+            answer: Some(answer),
+            status: Status::Ok,
+            check,
+            warnings: Vec::new(),
+            elapsed: Duration::from_micros(1500),
+        }
+    }
+
+    #[test]
+    fn it_escapes_json_strings() {
+        assert_eq!(json_string("a\"b\\c\nd"), "\"a\\\"b\\\\c\\nd\"");
+    }
+
+    #[test]
+    fn it_writes_runs_as_json() {
+        let runs = vec![run(
+            Answer::from("CMZ"),
+            Some(Check::Fail {
+                expected: "MCD".to_string(),
+            }),
+        )];
+
+        assert_eq!(
+            to_json(&runs),
+            "[{\"day\":5,\"part\":\"A\",\"answer\":\"CMZ\",\"elapsed_ms\":1.500,\"input\":\"input/day5\",\"status\":\"ok\",\"error\":null,\"check\":\"fail\",\"expected\":\"MCD\",\"warnings\":[]}]"
+        );
+    }
+
+    #[test]
+    fn it_writes_runs_as_csv() {
+        let runs = vec![run(Answer::Integer(95437), None)];
+
+        assert_eq!(
+            to_csv(&runs),
+            "day,part,answer,elapsed_ms,input,status,check\n5,A,95437,1.500,input/day5,ok,\n"
+        );
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+}
@@ -1,3 +1,11 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::Part;
+use std::hint::black_box;
+use std::io::{self, Read};
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
 pub mod day1;
 pub mod day2;
 pub mod day3;
@@ -5,3 +13,225 @@ pub mod day4;
 pub mod day5;
 pub mod day6;
 pub mod day7;
+
+/// A puzzle of a single day: the input is parsed once and then handed to each part.
+pub trait Solver {
+    type Input;
+
+    fn parse(&self, lines: Vec<String>) -> Result<Self::Input, ParseError>;
+    fn part_a(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+    fn part_b(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+
+    /// Problems in the input that did not stop parsing, but that a user should look at.
+    fn warnings(&self, _input: &Self::Input) -> Vec<ParseError> {
+        Vec::new()
+    }
+
+    /// A detailed account of the input for `--report`, if the day has one.
+    fn report(&self, _input: &Self::Input) -> Result<Option<String>, SolveError> {
+        Ok(None)
+    }
+
+    /// How `part` reaches its answer step by step for `--explain`, if the day can tell.
+    fn explain(&self, _input: &Self::Input, _part: Part) -> Result<Option<String>, SolveError> {
+        Ok(None)
+    }
+
+    /// Whether [`Solver::stream`] can solve from the raw input, so that it is worth opening.
+    fn streams(&self) -> bool {
+        false
+    }
+
+    /// Solves `part` straight from the raw input, without holding it in memory, for days whose
+    /// input can be too large to read as lines. `None` if the day only solves from lines.
+    fn stream(
+        &self,
+        _reader: &mut dyn Read,
+        _part: Part,
+    ) -> Option<io::Result<Result<Answer, SolveError>>> {
+        None
+    }
+}
+
+/// Type-erased view of a [`Solver`], so that days with different inputs can live in the registry.
+pub trait Puzzle {
+    fn run(&self, part: Part, lines: Vec<String>) -> Outcome;
+
+    fn report(&self, lines: Vec<String>) -> Result<Option<String>, SolveError>;
+
+    fn explain(&self, part: Part, lines: Vec<String>) -> Result<Option<String>, SolveError>;
+
+    fn streams(&self) -> bool;
+
+    fn stream(
+        &self,
+        reader: &mut dyn Read,
+        part: Part,
+    ) -> Option<io::Result<Result<Answer, SolveError>>>;
+
+    #[cfg(test)]
+    fn solve(&self, part: Part, lines: Vec<String>) -> Result<Answer, SolveError> {
+        self.run(part, lines).answer
+    }
+
+    /// Times the parsing and the given parts separately, `iterations` times each after `warmup`
+    /// untimed runs.
+    fn bench(
+        &self,
+        lines: &[String],
+        parts: &[Part],
+        warmup: usize,
+        iterations: usize,
+    ) -> Result<Timings, SolveError>;
+}
+
+/// The answer to a part, with the warnings raised while parsing its input.
+#[derive(Debug)]
+pub struct Outcome {
+    pub answer: Result<Answer, SolveError>,
+    pub warnings: Vec<ParseError>,
+}
+
+/// Duration of every timed run, per phase.
+#[derive(Debug, Default)]
+pub struct Timings {
+    pub parse: Vec<Duration>,
+    pub part_a: Vec<Duration>,
+    pub part_b: Vec<Duration>,
+}
+
+impl<S: Solver> Puzzle for S {
+    fn run(&self, part: Part, lines: Vec<String>) -> Outcome {
+        let input = match self.parse(lines) {
+            Ok(input) => input,
+            Err(error) => {
+                return Outcome {
+                    answer: Err(error.into()),
+                    warnings: Vec::new(),
+                }
+            }
+        };
+        Outcome {
+            answer: match part {
+                Part::A => self.part_a(&input),
+                Part::B => self.part_b(&input),
+            },
+            warnings: self.warnings(&input),
+        }
+    }
+
+    fn report(&self, lines: Vec<String>) -> Result<Option<String>, SolveError> {
+        let input = self.parse(lines)?;
+        Solver::report(self, &input)
+    }
+
+    fn explain(&self, part: Part, lines: Vec<String>) -> Result<Option<String>, SolveError> {
+        let input = self.parse(lines)?;
+        Solver::explain(self, &input, part)
+    }
+
+    fn streams(&self) -> bool {
+        Solver::streams(self)
+    }
+
+    fn stream(
+        &self,
+        reader: &mut dyn Read,
+        part: Part,
+    ) -> Option<io::Result<Result<Answer, SolveError>>> {
+        Solver::stream(self, reader, part)
+    }
+
+    fn bench(
+        &self,
+        lines: &[String],
+        parts: &[Part],
+        warmup: usize,
+        iterations: usize,
+    ) -> Result<Timings, SolveError> {
+        let mut timings = Timings::default();
+        for iteration in 0..warmup + iterations {
+            let timed = iteration >= warmup;
+            let lines = lines.to_vec();
+            let start = Instant::now();
+            let input = black_box(self.parse(lines)?);
+            if timed {
+                timings.parse.push(start.elapsed());
+            }
+            for part in parts {
+                let start = Instant::now();
+                black_box(match part {
+                    Part::A => self.part_a(&input)?,
+                    Part::B => self.part_b(&input)?,
+                });
+                if timed {
+                    match part {
+                        Part::A => timings.part_a.push(start.elapsed()),
+                        Part::B => timings.part_b.push(start.elapsed()),
+                    }
+                }
+            }
+        }
+        Ok(timings)
+    }
+}
+
+/// Options of the days that take any, set from the command line.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub top: Option<NonZeroUsize>,
+    pub rules: day2::Rules,
+    pub group_size: Option<NonZeroUsize>,
+    pub sections: Vec<i64>,
+    pub window: Option<NonZeroUsize>,
+    pub disk: day7::Disk,
+    pub show: day7::Show,
+    pub tree: day7::TreeView,
+}
+
+/// Every implemented day, in order, set up with `config`.
+pub fn registry(config: &Config) -> Vec<(u8, Box<dyn Puzzle>)> {
+    vec![
+        (1, Box::new(day1::Day1 { top: config.top })),
+        (
+            2,
+            Box::new(day2::Day2 {
+                rules: config.rules.clone(),
+            }),
+        ),
+        (
+            3,
+            Box::new(day3::Day3 {
+                group_size: config.group_size,
+            }),
+        ),
+        (
+            4,
+            Box::new(day4::Day4 {
+                sections: config.sections.clone(),
+            }),
+        ),
+        (5, Box::new(day5::Day5)),
+        (
+            6,
+            Box::new(day6::Day6 {
+                window: config.window,
+            }),
+        ),
+        (
+            7,
+            Box::new(day7::Day7 {
+                disk: config.disk,
+                show: config.show,
+                view: config.tree.clone(),
+            }),
+        ),
+    ]
+}
+
+pub fn find(day: u8, config: &Config) -> Option<Box<dyn Puzzle>> {
+    registry(config)
+        .into_iter()
+        .find(|(d, _)| *d == day)
+        .map(|(_, puzzle)| puzzle)
+}
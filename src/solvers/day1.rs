@@ -1,33 +1,194 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::solvers::Solver;
 use crate::Part;
+use inventory::Inventory;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::num::NonZeroUsize;
 
-pub fn solve(part: Part, lines: Vec<String>) -> String {
-    let mut carried_calories: Vec<u32> = lines.into_iter().fold(vec![0], |mut acc, b| {
-        if b.is_empty() {
-            acc.push(0);
-        } else {
-            let last_calories = acc.last_mut().unwrap();
-            *last_calories += b.parse::<u32>().unwrap();
-        }
-        acc
-    });
-    match part {
-        Part::A => {
-            let result = carried_calories.iter().max().unwrap();
-            String::from(format!("{}", result))
+mod inventory;
+
+/// Number of elves whose calories part B adds up, unless told otherwise.
+const TOP: usize = 3;
+/// Number of ranges of calories in the histogram of the report.
+const BUCKETS: usize = 10;
+/// Length of the bar of the most common range of calories in the histogram.
+const BAR_WIDTH: usize = 40;
+
+#[derive(Default)]
+pub struct Day1 {
+    /// Number of elves whose calories part B adds up.
+    pub top: Option<NonZeroUsize>,
+}
+
+impl Solver for Day1 {
+    type Input = Inventory;
+
+    fn parse(&self, lines: Vec<String>) -> Result<Inventory, ParseError> {
+        lines
+            .into_iter()
+            .enumerate()
+            .try_fold(Inventory::default(), |mut acc, (index, b)| {
+                if b.is_empty() {
+                    acc.next_elf();
+                } else {
+                    let calories = b.parse::<u32>().map_err(|_| {
+                        ParseError::line(&b, "expected a number of calories").on_line(index)
+                    })?;
+                    acc.add(calories).ok_or_else(|| {
+                        ParseError::line(&b, "total calories overflow").on_line(index)
+                    })?;
+                }
+                Ok(acc)
+            })
+    }
+
+    fn part_a(&self, inventory: &Inventory) -> Result<Answer, SolveError> {
+        let result = inventory.totals().max().unwrap();
+        Ok(Answer::from(result))
+    }
+
+    fn part_b(&self, inventory: &Inventory) -> Result<Answer, SolveError> {
+        let top = top_elves(inventory.totals(), self.top())?;
+        let result: u64 = top.iter().map(|(_, calories)| u64::from(*calories)).sum();
+        Ok(Answer::from(result))
+    }
+
+    fn explain(&self, inventory: &Inventory, part: Part) -> Result<Option<String>, SolveError> {
+        let n = match part {
+            Part::A => 1,
+            Part::B => self.top(),
+        };
+        let mut explanation = format!(
+            "{:>4}  {:>5}  {:>8}  {:>8}\n",
+            "RANK", "ELF", "CALORIES", "TOTAL"
+        );
+        let mut total: u64 = 0;
+        for (rank, (elf, calories)) in top_elves(inventory.totals(), n)?.into_iter().enumerate() {
+            total += u64::from(calories);
+            explanation.push_str(&format!(
+                "{:>4}  {:>5}  {:>8}  {:>8}\n",
+                rank + 1,
+                elf + 1,
+                calories,
+                total
+            ));
         }
-        Part::B => {
-            carried_calories.sort();
-            carried_calories.reverse();
-            let result: u32 = carried_calories[..3].iter().sum();
-            String::from(format!("{}", result))
+        Ok(Some(explanation))
+    }
+
+    fn report(&self, inventory: &Inventory) -> Result<Option<String>, SolveError> {
+        Ok(Some(inventory_report(inventory)))
+    }
+}
+
+impl Day1 {
+    fn top(&self) -> usize {
+        self.top.map_or(TOP, NonZeroUsize::get)
+    }
+}
+
+/// Describes how the calories are spread among the elves.
+fn inventory_report(inventory: &Inventory) -> String {
+    let elves = inventory.elves();
+    let items: usize = elves.iter().map(|elf| elf.items.len()).sum();
+    let calories: u64 = inventory.totals().map(u64::from).sum();
+
+    let mut report = format!(
+        "{} elves carry {} items, {} calories\n",
+        elves.len(),
+        items,
+        calories
+    );
+    report.push_str(&format!(
+        "mean {:.1}, median {:.1}, standard deviation {:.1}\n",
+        inventory.mean(),
+        inventory.median(),
+        inventory.std_dev()
+    ));
+    let ties = inventory.ties();
+    if ties.is_empty() {
+        report.push_str("no elves carry the same total\n");
+    }
+    for (total, tied) in ties {
+        let tied: Vec<String> = tied.iter().map(|elf| (elf + 1).to_string()).collect();
+        report.push_str(&format!("tied at {}: elves {}\n", total, tied.join(", ")));
+    }
+
+    let histogram = inventory.histogram(BUCKETS);
+    let most = histogram.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    report.push_str(&format!("{:>13}  {:>5}\n", "CALORIES", "ELVES"));
+    for (interval, count) in histogram {
+        // a bar never vanishes while an elf is counted in it
+        let bar = (count * BAR_WIDTH).div_ceil(most.max(1));
+        report.push_str(
+            format!(
+                "{:>13}  {:>5}  {}",
+                interval.to_string(),
+                count,
+                "#".repeat(bar)
+            )
+            .trim_end(),
+        );
+        report.push('\n');
+    }
+
+    report.push_str(&format!(
+        "{:>5}  {:>5}  {:>8}\n",
+        "ELF", "ITEMS", "CALORIES"
+    ));
+    for (index, elf) in elves.iter().enumerate() {
+        report.push_str(&format!(
+            "{:>5}  {:>5}  {:>8}\n",
+            index + 1,
+            elf.items.len(),
+            elf.total
+        ));
+    }
+    report
+}
+
+/// The `n` elves carrying the most calories, as their index and total, from the most to the least.
+/// Of elves carrying the same total the first ones come first.
+///
+/// Only the `n` best elves seen so far are kept, in a min-heap whose top is the next one to
+/// drop, so the totals are read once without sorting all of them.
+fn top_elves(
+    totals: impl IntoIterator<Item = u32>,
+    n: usize,
+) -> Result<Vec<(usize, u32)>, SolveError> {
+    // grows with the elves read rather than with `n`, which may be far larger
+    let mut heap = BinaryHeap::new();
+    let mut elves = 0;
+    for (index, calories) in totals.into_iter().enumerate() {
+        heap.push(Reverse((calories, Reverse(index))));
+        if heap.len() > n {
+            heap.pop();
         }
+        elves += 1;
+    }
+    if elves < n {
+        return Err(SolveError::no_answer(format!(
+            "expected at least {} elves, found {}",
+            n, elves
+        )));
     }
+    // sorting the reversed entries puts the most calories first
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((calories, Reverse(index)))| (index, calories))
+        .collect())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solvers::day1::solve;
+    use crate::answer::Answer;
+    use crate::solvers::day1::{inventory_report, top_elves, Day1};
+    use crate::solvers::{Puzzle, Solver};
     use crate::Part;
+    use std::num::NonZeroUsize;
 
     #[test]
     fn solve_example_part_a() {
@@ -47,8 +208,8 @@ mod tests {
             "".to_string(),
             "10000".to_string(),
         ];
-        let result = solve(Part::A, lines);
-        assert_eq!(result, "24000");
+        let result = Day1::default().solve(Part::A, lines);
+        assert_eq!(result, Ok(Answer::Integer(24000)));
     }
 
     #[test]
@@ -69,7 +230,123 @@ mod tests {
             "".to_string(),
             "10000".to_string(),
         ];
-        let result = solve(Part::B, lines);
-        assert_eq!(result, "45000");
+        let result = Day1::default().solve(Part::B, lines);
+        assert_eq!(result, Ok(Answer::Integer(45000)));
+    }
+
+    #[test]
+    fn it_rejects_lines_that_are_not_calories() {
+        let lines = vec!["1000".to_string(), "".to_string(), "20x0".to_string()];
+        let error = Day1::default().parse(lines).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.text, "20x0");
+    }
+
+    #[test]
+    fn it_needs_three_elves_for_part_b() {
+        let lines = vec!["1000".to_string(), "".to_string(), "2000".to_string()];
+        let result = Day1::default().solve(Part::B, lines);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_keeps_the_elves_carrying_the_most() {
+        let totals = [6000, 4000, 11000, 24000, 10000, 11000];
+
+        assert_eq!(
+            top_elves(totals, 3),
+            Ok(vec![(3, 24000), (2, 11000), (5, 11000)])
+        );
+        assert_eq!(top_elves(totals, 1), Ok(vec![(3, 24000)]));
+        assert_eq!(top_elves(totals, 6).map(|top| top.len()), Ok(6));
+        assert!(top_elves(totals, 7).is_err());
+    }
+
+    #[test]
+    fn it_refuses_more_elves_than_there_are() {
+        assert!(top_elves([6000, 4000], 100_000_000_000).is_err());
+        assert!(top_elves([6000, 4000], usize::MAX).is_err());
+
+        let day = Day1 {
+            top: NonZeroUsize::new(100_000_000_000),
+        };
+        let result = day.solve(Part::B, vec!["1000".to_string()]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "expected at least 100000000000 elves, found 1"
+        );
+    }
+
+    #[test]
+    fn it_adds_up_any_number_of_elves() {
+        let lines = vec![
+            "1000".to_string(),
+            "".to_string(),
+            "3000".to_string(),
+            "".to_string(),
+            "2000".to_string(),
+        ];
+        let day = Day1 {
+            top: NonZeroUsize::new(2),
+        };
+
+        assert_eq!(day.solve(Part::B, lines.clone()), Ok(Answer::Integer(5000)));
+        assert_eq!(
+            Puzzle::explain(&day, Part::B, lines),
+            Ok(Some(
+                "RANK    ELF  CALORIES     TOTAL\n\
+                 \x20  1      2      3000      3000\n\
+                 \x20  2      3      2000      5000\n"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn it_reports_statistics_of_the_inventory() {
+        let lines = vec![
+            "1000".to_string(),
+            "2000".to_string(),
+            "3000".to_string(),
+            "".to_string(),
+            "4000".to_string(),
+            "".to_string(),
+            "5000".to_string(),
+            "6000".to_string(),
+            "".to_string(),
+            "7000".to_string(),
+            "8000".to_string(),
+            "9000".to_string(),
+            "".to_string(),
+            "10000".to_string(),
+            "".to_string(),
+            "4000".to_string(),
+        ];
+        let inventory = Day1::default().parse(lines).unwrap();
+
+        assert_eq!(
+            inventory_report(&inventory),
+            "6 elves carry 11 items, 59000 calories\n\
+             mean 9833.3, median 8000.0, standard deviation 6890.0\n\
+             tied at 4000: elves 2, 6\n\
+             \x20    CALORIES  ELVES\n\
+             \x20   4000-6000      3  ########################################\n\
+             \x20   6001-8001      0\n\
+             \x20  8002-10002      1  ##############\n\
+             \x20 10003-12003      1  ##############\n\
+             \x20 12004-14004      0\n\
+             \x20 14005-16005      0\n\
+             \x20 16006-18006      0\n\
+             \x20 18007-20007      0\n\
+             \x20 20008-22008      0\n\
+             \x20 22009-24000      1  ##############\n\
+             \x20 ELF  ITEMS  CALORIES\n\
+             \x20   1      3      6000\n\
+             \x20   2      1      4000\n\
+             \x20   3      2     11000\n\
+             \x20   4      3     24000\n\
+             \x20   5      1     10000\n\
+             \x20   6      1      4000\n"
+        );
     }
 }
@@ -0,0 +1,172 @@
+use crate::interval::Interval;
+use std::collections::BTreeMap;
+
+/// The items an elf carries, by their calories.
+#[derive(Debug, Default, PartialEq)]
+pub struct Elf {
+    pub items: Vec<u32>,
+    pub total: u32,
+}
+
+/// The calories of every item, grouped by the elf carrying them in the order of the list.
+///
+/// There is always at least one elf, possibly carrying nothing, as an empty list still starts one.
+#[derive(Debug, PartialEq)]
+pub struct Inventory {
+    elves: Vec<Elf>,
+}
+
+impl Default for Inventory {
+    fn default() -> Inventory {
+        Inventory {
+            elves: vec![Elf::default()],
+        }
+    }
+}
+
+impl Inventory {
+    /// Starts the list of the next elf.
+    pub fn next_elf(&mut self) {
+        self.elves.push(Elf::default());
+    }
+
+    /// Gives an item to the last elf, or `None` if its total would overflow.
+    pub fn add(&mut self, calories: u32) -> Option<()> {
+        let elf = self.elves.last_mut()?;
+        elf.total = elf.total.checked_add(calories)?;
+        elf.items.push(calories);
+        Some(())
+    }
+
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    pub fn totals(&self) -> impl Iterator<Item = u32> + '_ {
+        self.elves.iter().map(|elf| elf.total)
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.totals().map(f64::from).sum::<f64>() / self.elves.len() as f64
+    }
+
+    /// The middle total, or the mean of the two middle ones for an even number of elves.
+    pub fn median(&self) -> f64 {
+        let mut totals: Vec<u32> = self.totals().collect();
+        totals.sort_unstable();
+        let middle = totals.len() / 2;
+        if totals.len().is_multiple_of(2) {
+            (f64::from(totals[middle - 1]) + f64::from(totals[middle])) / 2.0
+        } else {
+            f64::from(totals[middle])
+        }
+    }
+
+    /// Standard deviation of the totals over all the elves.
+    pub fn std_dev(&self) -> f64 {
+        let mean = self.mean();
+        let variance = self
+            .totals()
+            .map(|total| (f64::from(total) - mean).powi(2))
+            .sum::<f64>()
+            / self.elves.len() as f64;
+        variance.sqrt()
+    }
+
+    /// Every total carried by more than one elf, with the indexes of those elves, from the largest
+    /// total down.
+    pub fn ties(&self) -> Vec<(u32, Vec<usize>)> {
+        let mut elves_by_total: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+        for (index, total) in self.totals().enumerate() {
+            elves_by_total.entry(total).or_default().push(index);
+        }
+        elves_by_total
+            .into_iter()
+            .rev()
+            .filter(|(_, elves)| elves.len() > 1)
+            .collect()
+    }
+
+    /// Number of elves whose total falls in each of at most `buckets` ranges of the same length,
+    /// which together span the smallest to the largest total.
+    pub fn histogram(&self, buckets: usize) -> Vec<(Interval, usize)> {
+        let min = i64::from(self.totals().min().unwrap_or(0));
+        let max = i64::from(self.totals().max().unwrap_or(0));
+        let buckets = (buckets.max(1) as i64).min(max - min + 1);
+        // rounded up so that the last range reaches the largest total
+        let width = (max - min + buckets) / buckets;
+        let mut counts = vec![0; buckets as usize];
+        for total in self.totals() {
+            counts[((i64::from(total) - min) / width) as usize] += 1;
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .filter_map(|(bucket, count)| {
+                let start = min + bucket as i64 * width;
+                Some((Interval::new(start, (start + width - 1).min(max))?, count))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interval::Interval;
+    use crate::solvers::day1::inventory::Inventory;
+
+    fn inventory(elves: &[&[u32]]) -> Inventory {
+        let mut inventory = Inventory::default();
+        for (index, items) in elves.iter().enumerate() {
+            if index > 0 {
+                inventory.next_elf();
+            }
+            for calories in *items {
+                inventory.add(*calories).unwrap();
+            }
+        }
+        inventory
+    }
+
+    #[test]
+    fn it_totals_the_items_of_each_elf() {
+        let inventory = inventory(&[&[1000, 2000], &[], &[4000]]);
+
+        assert_eq!(inventory.elves().len(), 3);
+        assert_eq!(inventory.elves()[0].items, vec![1000, 2000]);
+        assert_eq!(inventory.totals().collect::<Vec<_>>(), vec![3000, 0, 4000]);
+
+        let mut full = Inventory::default();
+        full.add(u32::MAX).unwrap();
+        assert_eq!(full.add(1), None);
+    }
+
+    #[test]
+    fn it_computes_statistics_of_the_totals() {
+        let inventory = inventory(&[&[2], &[4], &[4], &[4], &[5], &[5], &[7], &[9]]);
+
+        assert_eq!(inventory.mean(), 5.0);
+        assert_eq!(inventory.median(), 4.5);
+        assert_eq!(inventory.std_dev(), 2.0);
+        assert_eq!(inventory.ties(), vec![(5, vec![4, 5]), (4, vec![1, 2, 3])]);
+    }
+
+    #[test]
+    fn it_buckets_the_totals() {
+        let inventory = inventory(&[&[2], &[4], &[4], &[4], &[5], &[5], &[7], &[9]]);
+
+        assert_eq!(
+            inventory.histogram(3),
+            vec![
+                (Interval::new(2, 4).unwrap(), 4),
+                (Interval::new(5, 7).unwrap(), 3),
+                (Interval::new(8, 9).unwrap(), 1)
+            ]
+        );
+        assert_eq!(inventory.histogram(20).len(), 8);
+        assert_eq!(
+            Inventory::default().histogram(10),
+            vec![(Interval::new(0, 0).unwrap(), 1)]
+        );
+    }
+}
@@ -1,258 +1,470 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::solvers::Solver;
 use crate::Part;
-use std::cmp::Ordering;
-
-pub fn solve(part: Part, lines: Vec<String>) -> String {
-    let scores: Vec<u32> = lines
-        .into_iter()
-        .map(|l| match part {
-            Part::A => Game::new_a(l),
-            Part::B => Game::new_b(l),
-        })
-        .map(Game::score)
-        .collect();
+use optimizer::Reading;
+pub use rules::Rules;
+use std::fmt;
 
-    let result: u32 = scores.iter().sum();
-    String::from(format!("{}", result))
-}
+mod optimizer;
+mod rules;
 
-struct Game {
-    opponent: Values,
-    me: Values,
+#[derive(Default)]
+pub struct Day2 {
+    /// The game the strategy guide is written for, rock-paper-scissors by default.
+    pub rules: Rules,
 }
 
-impl Game {
-    fn new_a(line: String) -> Game {
-        let mut split = line.split(' ');
-        return Game {
-            opponent: Values::from(split.next().unwrap()),
-            me: Values::from(split.next().unwrap()),
-        };
+impl Solver for Day2 {
+    type Input = Vec<Round>;
+
+    fn parse(&self, lines: Vec<String>) -> Result<Vec<Round>, ParseError> {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| Round::parse(line, &self.rules).map_err(|e| e.on_line(index)))
+            .collect()
     }
 
-    fn new_b(line: String) -> Game {
-        let mut split = line.split(' ');
-        let opponent = Values::from(split.next().unwrap());
-        return Game {
-            opponent: opponent,
-            me: match split.next().unwrap() {
-                "X" => match opponent {
-                    Values::Rock => Values::Scissors,
-                    Values::Paper => Values::Rock,
-                    Values::Scissors => Values::Paper,
-                },
-                "Y" => opponent.clone(),
-                "Z" => match opponent {
-                    Values::Rock => Values::Paper,
-                    Values::Paper => Values::Scissors,
-                    Values::Scissors => Values::Rock,
-                },
-                _ => panic!("invalid value"),
-            },
-        };
+    fn part_a(&self, rounds: &Vec<Round>) -> Result<Answer, SolveError> {
+        self.total_score(rounds, Part::A)
     }
 
-    fn score(self) -> u32 {
-        return if self.me == self.opponent {
-            self.me.shape_value() + 3
-        } else if self.me > self.opponent {
-            self.me.shape_value() + 6
-        } else {
-            self.me.shape_value()
-        };
+    fn part_b(&self, rounds: &Vec<Round>) -> Result<Answer, SolveError> {
+        self.total_score(rounds, Part::B)
     }
-}
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-enum Values {
-    Rock,
-    Paper,
-    Scissors,
-}
+    fn explain(&self, rounds: &Vec<Round>, part: Part) -> Result<Option<String>, SolveError> {
+        let mut explanation = format!(
+            "{:>5}  {:<5}  {:<8}  {:<8}  {:<7}  {:>5}  {:>7}  {:>6}\n",
+            "ROUND", "GUIDE", "OPPONENT", "ME", "OUTCOME", "SHAPE", "RESULT", "TOTAL"
+        );
+        let shapes = self.rules.shapes();
+        let mut total: u64 = 0;
+        for (index, (round, game)) in rounds.iter().zip(self.games(rounds, part)?).enumerate() {
+            let outcome = self.rules.outcome(game.me, game.opponent);
+            total = total
+                .checked_add(game.score(&self.rules))
+                .ok_or_else(score_overflow)?;
+            explanation.push_str(&format!(
+                "{:>5}  {:<5}  {:<8}  {:<8}  {:<7}  {:>5}  {:>7}  {:>6}\n",
+                index + 1,
+                round.line(&self.rules),
+                shapes[game.opponent].name,
+                shapes[game.me].name,
+                outcome,
+                shapes[game.me].score,
+                outcome.score(),
+                total
+            ));
+        }
+        Ok(Some(explanation))
+    }
 
-impl Values {
-    fn from(s: &str) -> Values {
-        match s {
-            "A" => Values::Rock,
-            "X" => Values::Rock,
-            "B" => Values::Paper,
-            "Y" => Values::Paper,
-            "C" => Values::Scissors,
-            "Z" => Values::Scissors,
-            _ => {
-                panic!("invalid value");
+    fn report(&self, rounds: &Vec<Round>) -> Result<Option<String>, SolveError> {
+        let ranking = optimizer::rank(rounds, &self.rules);
+        let (part_a, part_b) = (Reading::part_a(&self.rules), Reading::part_b());
+
+        let mut report = format!("{:>6}  READING\n", "TOTAL");
+        for (index, scored) in ranking.iter().enumerate() {
+            let mut notes = Vec::new();
+            if index == 0 {
+                notes.push("best");
+            }
+            if index + 1 == ranking.len() {
+                notes.push("worst");
             }
+            if scored.reading == part_a {
+                notes.push("part A");
+            }
+            if scored.reading == part_b {
+                notes.push("part B");
+            }
+            report.push_str(
+                format!(
+                    "{:>6}  {:<26}  {}",
+                    scored.total,
+                    scored.reading.describe(&self.rules),
+                    notes.join(", ")
+                )
+                .trim_end(),
+            );
+            report.push('\n');
         }
+        Ok(Some(report))
+    }
+}
+
+impl Day2 {
+    fn total_score(&self, rounds: &[Round], part: Part) -> Result<Answer, SolveError> {
+        let result = self
+            .games(rounds, part)?
+            .into_iter()
+            .try_fold(0u64, |total, game| {
+                total.checked_add(game.score(&self.rules))
+            })
+            .ok_or_else(score_overflow)?;
+        Ok(Answer::from(result))
     }
-    fn shape_value(self) -> u32 {
-        match self {
-            Values::Rock => 1,
-            Values::Paper => 2,
-            Values::Scissors => 3,
+
+    /// How `part` understands the second column of the strategy guide.
+    fn reading(&self, part: Part) -> Reading {
+        match part {
+            Part::A => Reading::part_a(&self.rules),
+            Part::B => Reading::part_b(),
         }
     }
+
+    /// Plays every round of the strategy guide the way `part` reads it, pointing at the second
+    /// column of a round that `part` does not understand.
+    fn games(&self, rounds: &[Round], part: Part) -> Result<Vec<Game>, SolveError> {
+        let reading = self.reading(part);
+        rounds
+            .iter()
+            .enumerate()
+            .map(|(index, round)| {
+                reading.game(*round, &self.rules).map_err(|problem| {
+                    let line = round.line(&self.rules);
+                    let column = line.split_once(' ').map_or(line.as_str(), |(_, me)| me);
+                    ParseError::at(&line, column, problem).on_line(index).into()
+                })
+            })
+            .collect()
+    }
+}
+
+fn score_overflow() -> SolveError {
+    SolveError::no_answer("the total score does not fit in 64 bits")
+}
+
+fn split_round(line: &str) -> Result<(&str, &str), ParseError> {
+    match line.split_once(' ') {
+        Some((opponent, me)) => Ok((opponent, me)),
+        None => Err(ParseError::line(
+            line,
+            "expected two letters separated by a space",
+        )),
+    }
+}
+
+/// Position of the single letter `s` among `letters`.
+fn letter_index(s: &str, letters: &str) -> Option<usize> {
+    let mut chars = s.chars();
+    let (Some(letter), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    letters.chars().position(|l| l == letter)
 }
 
-impl PartialOrd for Values {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self {
-            Values::Rock => match other {
-                Values::Rock => Some(Ordering::Equal),
-                Values::Paper => Some(Ordering::Less),
-                Values::Scissors => Some(Ordering::Greater),
-            },
-            Values::Paper => match other {
-                Values::Rock => Some(Ordering::Greater),
-                Values::Paper => Some(Ordering::Equal),
-                Values::Scissors => Some(Ordering::Less),
-            },
-            Values::Scissors => match other {
-                Values::Rock => Some(Ordering::Less),
-                Values::Paper => Some(Ordering::Greater),
-                Values::Scissors => Some(Ordering::Equal),
-            },
+/// An error message listing the `letters` allowed, like `expected A, B or C`.
+fn expected(letters: &str) -> String {
+    let letters: Vec<String> = letters.chars().map(String::from).collect();
+    match letters.split_last() {
+        Some((last, rest)) if !rest.is_empty() => {
+            format!("expected {} or {}", rest.join(", "), last)
         }
+        _ => format!("expected {}", letters.concat()),
+    }
+}
+
+/// A line of the strategy guide, before deciding what its second column means.
+#[derive(Debug, Clone, Copy)]
+pub struct Round {
+    opponent: rules::Shape,
+    letter: char,
+}
+
+impl Round {
+    /// Reads a line whose second column is a letter that either part understands.
+    fn parse(line: &str, rules: &Rules) -> Result<Round, ParseError> {
+        let (opponent, column) = split_round(line)?;
+        let opponents = rules.opponent_letters();
+        let mine = rules.my_letters();
+        let letters: String = mine
+            .chars()
+            .chain(OUTCOME_LETTERS.chars().filter(|l| !mine.contains(*l)))
+            .collect();
+        Ok(Round {
+            opponent: letter_index(opponent, &opponents)
+                .ok_or_else(|| ParseError::at(line, opponent, expected(&opponents)))?,
+            letter: letter_index(column, &letters)
+                .and(column.chars().next())
+                .ok_or_else(|| ParseError::at(line, column, expected(&letters)))?,
+        })
+    }
+
+    /// The line of the guide the round was read from, as `parse` takes nothing but two letters
+    /// separated by a space.
+    fn line(&self, rules: &Rules) -> String {
+        format!("{} {}", rules.shapes()[self.opponent].opponent, self.letter)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Game {
+    opponent: rules::Shape,
+    me: rules::Shape,
+}
+
+impl Game {
+    /// Score of the game, which cannot overflow as a shape scores at most `u32::MAX`.
+    fn score(self, rules: &Rules) -> u64 {
+        u64::from(rules.shapes()[self.me].score)
+            + u64::from(rules.outcome(self.me, self.opponent).score())
+    }
+}
+
+/// The letters of the outcomes when the second column names one.
+const OUTCOME_LETTERS: &str = "XYZ";
+const OUTCOMES: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    fn index(self) -> usize {
+        OUTCOMES
+            .iter()
+            .position(|outcome| *outcome == self)
+            .unwrap_or(0)
+    }
+
+    fn score(self) -> u32 {
+        self.index() as u32 * 3
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Outcome::Loss => "loss",
+            Outcome::Draw => "draw",
+            Outcome::Win => "win",
+        };
+        f.pad(name)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solvers::day2::solve;
-    use crate::solvers::day2::{Game, Values};
+    use crate::answer::Answer;
+    use crate::solvers::day2::{Day2, Game, Round, Rules};
+    use crate::solvers::{Puzzle, Solver};
     use crate::Part;
 
+    const ROCK: usize = 0;
+    const PAPER: usize = 1;
+    const SCISSORS: usize = 2;
+
+    /// The game a line of the guide stands for when `part` reads it with the default rules.
+    fn play(line: &str, part: Part) -> Game {
+        let day = Day2::default();
+        let round = Round::parse(line, &day.rules).unwrap();
+        day.reading(part).game(round, &day.rules).unwrap()
+    }
+
     #[test]
     fn solve_example_part_a() {
         let lines = vec!["A Y".to_string(), "B X".to_string(), "C Z".to_string()];
-        let result = solve(Part::A, lines);
-        assert_eq!(result, "15");
+        let result = Day2::default().solve(Part::A, lines);
+        assert_eq!(result, Ok(Answer::Integer(15)));
     }
 
     #[test]
     fn solve_example_part_b() {
         let lines = vec!["A Y".to_string(), "B X".to_string(), "C Z".to_string()];
-        let result = solve(Part::B, lines);
-        assert_eq!(result, "12");
+        let result = Day2::default().solve(Part::B, lines);
+        assert_eq!(result, Ok(Answer::Integer(12)));
+    }
+
+    #[test]
+    fn it_rejects_unknown_letters() {
+        let lines = vec!["A X".to_string(), "A W".to_string()];
+        let error = Day2::default().parse(lines).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert!(Day2::default().parse(vec!["D Y".to_string()]).is_err());
+        assert!(Day2::default().parse(vec!["AY".to_string()]).is_err());
     }
 
     #[test]
     fn it_creates_game_of_rocks() {
-        let game = Game::new_a(String::from("A X"));
-        assert_eq!(game.opponent, Values::Rock);
-        assert_eq!(game.me, Values::Rock);
+        let game = play("A X", Part::A);
+        assert_eq!(game.opponent, ROCK);
+        assert_eq!(game.me, ROCK);
     }
 
     #[test]
     fn it_creates_game_of_papers() {
-        let game = Game::new_a(String::from("B Y"));
-        assert_eq!(game.opponent, Values::Paper);
-        assert_eq!(game.me, Values::Paper);
+        let game = play("B Y", Part::A);
+        assert_eq!(game.opponent, PAPER);
+        assert_eq!(game.me, PAPER);
     }
 
     #[test]
     fn it_creates_game_of_scissors() {
-        let game = Game::new_a(String::from("C Z"));
-        assert_eq!(game.opponent, Values::Scissors);
-        assert_eq!(game.me, Values::Scissors);
+        let game = play("C Z", Part::A);
+        assert_eq!(game.opponent, SCISSORS);
+        assert_eq!(game.me, SCISSORS);
     }
 
     #[test]
     fn it_creates_games_that_i_should_lose() {
-        let vs_rock = Game::new_b(String::from("A X"));
-        assert_eq!(vs_rock.opponent, Values::Rock);
-        assert_eq!(vs_rock.me, Values::Scissors);
-        let vs_paper = Game::new_b(String::from("B X"));
-        assert_eq!(vs_paper.opponent, Values::Paper);
-        assert_eq!(vs_paper.me, Values::Rock);
-        let vs_scissors = Game::new_b(String::from("C X"));
-        assert_eq!(vs_scissors.opponent, Values::Scissors);
-        assert_eq!(vs_scissors.me, Values::Paper);
+        let vs_rock = play("A X", Part::B);
+        assert_eq!(vs_rock.opponent, ROCK);
+        assert_eq!(vs_rock.me, SCISSORS);
+        let vs_paper = play("B X", Part::B);
+        assert_eq!(vs_paper.opponent, PAPER);
+        assert_eq!(vs_paper.me, ROCK);
+        let vs_scissors = play("C X", Part::B);
+        assert_eq!(vs_scissors.opponent, SCISSORS);
+        assert_eq!(vs_scissors.me, PAPER);
     }
     #[test]
     fn it_creates_games_that_should_ends_draw() {
-        let vs_rock = Game::new_b(String::from("A Y"));
-        assert_eq!(vs_rock.opponent, Values::Rock);
-        assert_eq!(vs_rock.me, Values::Rock);
-        let vs_paper = Game::new_b(String::from("B Y"));
-        assert_eq!(vs_paper.opponent, Values::Paper);
-        assert_eq!(vs_paper.me, Values::Paper);
-        let vs_scissors = Game::new_b(String::from("C Y"));
-        assert_eq!(vs_scissors.opponent, Values::Scissors);
-        assert_eq!(vs_scissors.me, Values::Scissors);
+        let vs_rock = play("A Y", Part::B);
+        assert_eq!(vs_rock.opponent, ROCK);
+        assert_eq!(vs_rock.me, ROCK);
+        let vs_paper = play("B Y", Part::B);
+        assert_eq!(vs_paper.opponent, PAPER);
+        assert_eq!(vs_paper.me, PAPER);
+        let vs_scissors = play("C Y", Part::B);
+        assert_eq!(vs_scissors.opponent, SCISSORS);
+        assert_eq!(vs_scissors.me, SCISSORS);
     }
     #[test]
     fn it_creates_games_that_i_should_win() {
-        let vs_rock = Game::new_b(String::from("A Z"));
-        assert_eq!(vs_rock.opponent, Values::Rock);
-        assert_eq!(vs_rock.me, Values::Paper);
-        let vs_paper = Game::new_b(String::from("B Z"));
-        assert_eq!(vs_paper.opponent, Values::Paper);
-        assert_eq!(vs_paper.me, Values::Scissors);
-        let vs_scissors = Game::new_b(String::from("C Z"));
-        assert_eq!(vs_scissors.opponent, Values::Scissors);
-        assert_eq!(vs_scissors.me, Values::Rock);
-    }
-
-    #[test]
-    fn it_gives_to_each_values_proper_shape_score() {
-        assert_eq!(Values::Rock.shape_value(), 1);
-        assert_eq!(Values::Paper.shape_value(), 2);
-        assert_eq!(Values::Scissors.shape_value(), 3);
+        let vs_rock = play("A Z", Part::B);
+        assert_eq!(vs_rock.opponent, ROCK);
+        assert_eq!(vs_rock.me, PAPER);
+        let vs_paper = play("B Z", Part::B);
+        assert_eq!(vs_paper.opponent, PAPER);
+        assert_eq!(vs_paper.me, SCISSORS);
+        let vs_scissors = play("C Z", Part::B);
+        assert_eq!(vs_scissors.opponent, SCISSORS);
+        assert_eq!(vs_scissors.me, ROCK);
     }
 
     #[test]
     fn it_gives_the_total_score_during_draws() {
+        let rules = Rules::default();
         let rocks = Game {
-            opponent: Values::Rock,
-            me: Values::Rock,
+            opponent: ROCK,
+            me: ROCK,
         };
-        assert_eq!(rocks.score(), 4);
+        assert_eq!(rocks.score(&rules), 4);
         let papers = Game {
-            opponent: Values::Paper,
-            me: Values::Paper,
+            opponent: PAPER,
+            me: PAPER,
         };
-        assert_eq!(papers.score(), 5);
+        assert_eq!(papers.score(&rules), 5);
         let scissors = Game {
-            opponent: Values::Scissors,
-            me: Values::Scissors,
+            opponent: SCISSORS,
+            me: SCISSORS,
         };
-        assert_eq!(scissors.score(), 6);
+        assert_eq!(scissors.score(&rules), 6);
     }
 
     #[test]
     fn it_gives_the_total_score_during_wins() {
+        let rules = Rules::default();
         let rocks = Game {
-            opponent: Values::Rock,
-            me: Values::Paper,
+            opponent: ROCK,
+            me: PAPER,
         };
-        assert_eq!(rocks.score(), 8);
+        assert_eq!(rocks.score(&rules), 8);
         let papers = Game {
-            opponent: Values::Paper,
-            me: Values::Scissors,
+            opponent: PAPER,
+            me: SCISSORS,
         };
-        assert_eq!(papers.score(), 9);
+        assert_eq!(papers.score(&rules), 9);
         let scissors = Game {
-            opponent: Values::Scissors,
-            me: Values::Rock,
+            opponent: SCISSORS,
+            me: ROCK,
         };
-        assert_eq!(scissors.score(), 7);
+        assert_eq!(scissors.score(&rules), 7);
     }
 
     #[test]
     fn it_gives_the_total_score_during_loss() {
+        let rules = Rules::default();
         let rocks = Game {
-            opponent: Values::Rock,
-            me: Values::Scissors,
+            opponent: ROCK,
+            me: SCISSORS,
         };
-        assert_eq!(rocks.score(), 3);
+        assert_eq!(rocks.score(&rules), 3);
         let papers = Game {
-            opponent: Values::Paper,
-            me: Values::Rock,
+            opponent: PAPER,
+            me: ROCK,
         };
-        assert_eq!(papers.score(), 1);
+        assert_eq!(papers.score(&rules), 1);
         let scissors = Game {
-            opponent: Values::Scissors,
-            me: Values::Paper,
+            opponent: SCISSORS,
+            me: PAPER,
         };
-        assert_eq!(scissors.score(), 2);
+        assert_eq!(scissors.score(&rules), 2);
+    }
+
+    #[test]
+    fn it_plays_the_guide_with_other_rules() {
+        // rock-paper-scissors-lizard-Spock, every shape beating the two before it
+        let rules = Rules::parse(
+            "rock A V 1\nSpock B W 2\npaper C X 3\nlizard D Y 4\nscissors E Z 5\n\
+             paper > Spock\npaper > rock\nlizard > paper\nlizard > Spock\n\
+             scissors > lizard\nscissors > paper\nrock > scissors\nrock > lizard\n\
+             Spock > rock\nSpock > scissors\n",
+        )
+        .unwrap();
+        let day = Day2 { rules };
+        let lines = vec!["A X".to_string(), "D Z".to_string(), "E Y".to_string()];
+
+        // paper beats rock, scissors beats lizard, lizard loses to scissors
+        assert_eq!(
+            day.solve(Part::A, lines.clone()),
+            Ok(Answer::Integer(9 + 11 + 4))
+        );
+        // lizard is the first shape to lose to rock, rock the first to beat lizard
+        assert_eq!(day.solve(Part::B, lines), Ok(Answer::Integer(4 + 7 + 8)));
+        let lines = vec!["A X".to_string(), "A W".to_string()];
+        let error = day.solve(Part::B, lines).unwrap_err();
+        assert!(error.to_string().contains("expected X, Y or Z"));
+        assert!(error.to_string().contains("2 | A W"));
+        let error = day.solve(Part::A, vec!["F X".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("expected A, B, C, D or E"));
+    }
+
+    #[test]
+    fn it_adds_up_scores_beyond_32_bits() {
+        let rules = Rules::parse("rock A X 4000000000\npaper B Y 2\npaper > rock\n").unwrap();
+        let day = Day2 { rules };
+        let lines = vec!["A X".to_string(), "A X".to_string()];
+
+        assert_eq!(
+            day.solve(Part::A, lines.clone()),
+            Ok(Answer::Integer(8_000_000_006))
+        );
+        assert!(Puzzle::report(&day, lines)
+            .unwrap()
+            .unwrap()
+            .contains("8000000006"));
+    }
+
+    #[test]
+    fn it_explains_every_round() {
+        let lines = vec!["A Y".to_string(), "B X".to_string(), "C Z".to_string()];
+
+        assert_eq!(
+            Puzzle::explain(&Day2::default(), Part::B, lines),
+            Ok(Some(
+                "ROUND  GUIDE  OPPONENT  ME        OUTCOME  SHAPE   RESULT   TOTAL\n\
+                 \x20   1  A Y    rock      rock      draw         1        3       4\n\
+                 \x20   2  B X    paper     rock      loss         1        0       5\n\
+                 \x20   3  C Z    scissors  rock      win          1        6      12\n"
+                    .to_string()
+            ))
+        );
     }
 }
@@ -0,0 +1,199 @@
+use crate::solvers::day2::rules::{Rules, Shape};
+use crate::solvers::day2::{expected, Game, Outcome, Round, OUTCOMES, OUTCOME_LETTERS};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// A meaning of the second column of the strategy guide.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reading {
+    /// The shape to play for each of my letters of the rules.
+    Shapes(Vec<Shape>),
+    /// The outcome to aim for with each of X, Y and Z.
+    Outcomes(Vec<Outcome>),
+}
+
+impl Reading {
+    /// How part A reads the guide.
+    pub fn part_a(rules: &Rules) -> Reading {
+        Reading::Shapes((0..rules.shapes().len()).collect())
+    }
+
+    /// How part B reads the guide.
+    pub fn part_b() -> Reading {
+        Reading::Outcomes(OUTCOMES.to_vec())
+    }
+
+    /// Every way to give my letters distinct shapes, then every way to give X, Y and Z distinct
+    /// outcomes.
+    pub fn all(rules: &Rules) -> Vec<Reading> {
+        let shapes: Vec<Shape> = (0..rules.shapes().len()).collect();
+        let shapes = permutations(&shapes).into_iter().map(Reading::Shapes);
+        let outcomes = permutations(&OUTCOMES).into_iter().map(Reading::Outcomes);
+        shapes.chain(outcomes).collect()
+    }
+
+    /// The letters this reading gives a meaning to.
+    fn letters(&self, rules: &Rules) -> String {
+        match self {
+            Reading::Shapes(_) => rules.my_letters(),
+            Reading::Outcomes(_) => OUTCOME_LETTERS.to_string(),
+        }
+    }
+
+    /// The game `round` stands for, or why it stands for none.
+    pub fn game(&self, round: Round, rules: &Rules) -> Result<Game, String> {
+        let letters = self.letters(rules);
+        let position = letters
+            .chars()
+            .position(|letter| letter == round.letter)
+            .ok_or_else(|| expected(&letters))?;
+        let me = match self {
+            Reading::Shapes(shapes) => shapes[position],
+            Reading::Outcomes(outcomes) => {
+                let outcome = outcomes[position];
+                rules.against(round.opponent, outcome).ok_or_else(|| {
+                    format!(
+                        "no shape gets a {} against {}",
+                        outcome,
+                        rules.shapes()[round.opponent].name
+                    )
+                })?
+            }
+        };
+        Ok(Game {
+            opponent: round.opponent,
+            me,
+        })
+    }
+
+    /// Each letter with its meaning, like `X=rock Y=paper Z=scissors`.
+    pub fn describe(&self, rules: &Rules) -> String {
+        let meanings: Vec<String> = match self {
+            Reading::Shapes(shapes) => shapes
+                .iter()
+                .map(|shape| rules.shapes()[*shape].name.clone())
+                .collect(),
+            Reading::Outcomes(outcomes) => outcomes.iter().map(Outcome::to_string).collect(),
+        };
+        let pairs: Vec<String> = self
+            .letters(rules)
+            .chars()
+            .zip(meanings)
+            .map(|(letter, meaning)| format!("{}={}", letter, meaning))
+            .collect();
+        pairs.join(" ")
+    }
+}
+
+/// Every ordering of `items`, starting with their own.
+fn permutations<T: Copy>(items: &[T]) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return vec![Vec::new()];
+    }
+    let mut all = Vec::new();
+    for (index, first) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(index);
+        for tail in permutations(&rest) {
+            let mut ordering = vec![*first];
+            ordering.extend(tail);
+            all.push(ordering);
+        }
+    }
+    all
+}
+
+/// The total a reading of the guide scores.
+#[derive(Debug, PartialEq)]
+pub struct Scored {
+    pub reading: Reading,
+    /// Wide enough that no guide can overflow it.
+    pub total: u128,
+}
+
+/// Scores the guide under every reading that understands all of its rounds, from the best total
+/// to the worst.
+///
+/// Only the number of rounds of each opponent's shape and letter matters, so every reading is
+/// scored from those counts whatever the length of the guide. There are as many readings as
+/// orderings of the shapes though, which only stays cheap for a handful of shapes.
+pub fn rank(rounds: &[Round], rules: &Rules) -> Vec<Scored> {
+    let mut counts: BTreeMap<(Shape, char), u128> = BTreeMap::new();
+    for round in rounds {
+        *counts.entry((round.opponent, round.letter)).or_default() += 1;
+    }
+    let mut ranking: Vec<Scored> = Reading::all(rules)
+        .into_iter()
+        .filter_map(|reading| {
+            let total = counts
+                .iter()
+                .map(|(&(opponent, letter), count)| {
+                    let game = reading.game(Round { opponent, letter }, rules).ok()?;
+                    Some(count * u128::from(game.score(rules)))
+                })
+                .sum::<Option<u128>>()?;
+            Some(Scored { reading, total })
+        })
+        .collect();
+    ranking.sort_by_key(|scored| Reverse(scored.total));
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::day2::optimizer::{rank, Reading, Scored};
+    use crate::solvers::day2::rules::Rules;
+    use crate::solvers::day2::Round;
+
+    const ROCK: usize = 0;
+    const PAPER: usize = 1;
+    const SCISSORS: usize = 2;
+
+    #[test]
+    fn it_lists_every_reading_once() {
+        let rules = Rules::default();
+        let readings = Reading::all(&rules);
+
+        assert_eq!(readings.len(), 12);
+        assert_eq!(readings[0], Reading::part_a(&rules));
+        assert_eq!(readings[6], Reading::part_b());
+        assert_eq!(
+            Reading::part_b().describe(&rules),
+            "X=loss Y=draw Z=win".to_string()
+        );
+    }
+
+    #[test]
+    fn it_ranks_the_readings_of_the_example() {
+        let rules = Rules::default();
+        let rounds: Vec<Round> = ["A Y", "B X", "C Z"]
+            .iter()
+            .map(|line| Round::parse(line, &rules).unwrap())
+            .collect();
+        let ranking = rank(&rounds, &rules);
+
+        assert_eq!(ranking.len(), 12);
+        assert_eq!(
+            ranking.first(),
+            Some(&Scored {
+                reading: Reading::Shapes(vec![SCISSORS, PAPER, ROCK]),
+                total: 24
+            })
+        );
+        assert_eq!(
+            ranking.last(),
+            Some(&Scored {
+                reading: Reading::Shapes(vec![ROCK, SCISSORS, PAPER]),
+                total: 6
+            })
+        );
+        assert!(ranking.contains(&Scored {
+            reading: Reading::part_a(&rules),
+            total: 15
+        }));
+        assert!(ranking.contains(&Scored {
+            reading: Reading::part_b(),
+            total: 12
+        }));
+    }
+}
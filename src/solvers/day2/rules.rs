@@ -0,0 +1,306 @@
+use crate::error::ParseError;
+use crate::solvers::day2::Outcome;
+use std::fs;
+
+/// A shape of the game, as its position in the rules.
+pub type Shape = usize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeRule {
+    pub name: String,
+    /// Letter of the shape in the first column of the strategy guide.
+    pub opponent: char,
+    /// Letter of the shape in the second column, when that column names my shape.
+    pub me: char,
+    pub score: u32,
+}
+
+/// A game of cyclic dominance like rock-paper-scissors: its shapes, and which one wins when two
+/// different shapes meet.
+///
+/// A rules file declares one shape per line, by name, letters and score, and says which shape
+/// beats which with one line per pair of shapes:
+///
+/// ```text
+/// # name    opponent  me  score
+/// rock      A         X   1
+/// paper     B         Y   2
+/// scissors  C         Z   3
+/// paper > rock
+/// rock > scissors
+/// scissors > paper
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    shapes: Vec<ShapeRule>,
+    /// Whether the shape of the row beats the shape of the column.
+    beats: Vec<Vec<bool>>,
+}
+
+impl Default for Rules {
+    /// Rock, paper and scissors.
+    fn default() -> Rules {
+        let shape = |name: &str, opponent, me, score| ShapeRule {
+            name: name.to_string(),
+            opponent,
+            me,
+            score,
+        };
+        Rules::cyclic(vec![
+            shape("rock", 'A', 'X', 1),
+            shape("paper", 'B', 'Y', 2),
+            shape("scissors", 'C', 'Z', 3),
+        ])
+    }
+}
+
+impl Rules {
+    /// Rules where each shape beats the half of the others that come just before it, wrapping
+    /// around, so that the winner follows from the distance between two shapes modulo their
+    /// number.
+    fn cyclic(shapes: Vec<ShapeRule>) -> Rules {
+        let n = shapes.len();
+        let beats = (0..n)
+            .map(|a| {
+                (0..n)
+                    .map(|b| (1..=n / 2).contains(&((a + n - b) % n)))
+                    .collect()
+            })
+            .collect();
+        Rules { shapes, beats }
+    }
+
+    pub fn load(path: &str) -> Result<Rules, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Rules::parse(&content).map_err(|e| e.in_file(path).to_string()),
+            Err(e) => Err(format!("cannot read {}: {}", path, e)),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Rules, ParseError> {
+        let mut shapes: Vec<ShapeRule> = Vec::new();
+        // where each shape is declared, to point at it when a pair is left undecided
+        let mut declared: Vec<(usize, &str)> = Vec::new();
+        let mut pairs: Vec<(usize, &str, &str, &str)> = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [] => {}
+                [first, ..] if first.starts_with('#') => {}
+                [winner, ">", loser] => pairs.push((index, line, winner, loser)),
+                [name, opponent, me, score] => {
+                    let letter = |field: &str| {
+                        let mut chars = field.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(letter), None) => Ok(letter),
+                            _ => Err(ParseError::at(line, field, "expected a single letter")
+                                .on_line(index)),
+                        }
+                    };
+                    let shape = ShapeRule {
+                        name: name.to_string(),
+                        opponent: letter(opponent)?,
+                        me: letter(me)?,
+                        score: score.parse().map_err(|_| {
+                            ParseError::at(line, score, "expected a score").on_line(index)
+                        })?,
+                    };
+                    if let Some(other) = shapes.iter().position(|s| {
+                        s.name == shape.name || s.opponent == shape.opponent || s.me == shape.me
+                    }) {
+                        let message = format!(
+                            "repeats the name or a letter of the shape on line {}",
+                            declared[other].0 + 1
+                        );
+                        return Err(ParseError::line(line, message).on_line(index));
+                    }
+                    shapes.push(shape);
+                    declared.push((index, line));
+                }
+                _ => {
+                    return Err(ParseError::line(
+                        line,
+                        "expected a shape like 'rock A X 1' or a rule like 'paper > rock'",
+                    )
+                    .on_line(index))
+                }
+            }
+        }
+        if shapes.len() < 2 {
+            let last = content.lines().enumerate().last().unwrap_or((0, ""));
+            let (index, line) = declared.last().copied().unwrap_or(last);
+            return Err(ParseError::line(line, "expected at least two shapes").on_line(index));
+        }
+
+        let n = shapes.len();
+        let mut beats = vec![vec![false; n]; n];
+        // line of the rule deciding each pair
+        let mut decided: Vec<Vec<Option<usize>>> = vec![vec![None; n]; n];
+        for (index, line, winner, loser) in pairs {
+            let find = |name: &str| {
+                shapes
+                    .iter()
+                    .position(|shape| shape.name == name)
+                    .ok_or_else(|| ParseError::at(line, name, "unknown shape").on_line(index))
+            };
+            let (a, b) = (find(winner)?, find(loser)?);
+            if a == b {
+                return Err(ParseError::line(line, "a shape cannot beat itself").on_line(index));
+            }
+            if let Some(earlier) = decided[a][b] {
+                let message = format!("line {} already decides between these shapes", earlier + 1);
+                return Err(ParseError::line(line, message).on_line(index));
+            }
+            decided[a][b] = Some(index);
+            decided[b][a] = Some(index);
+            beats[a][b] = true;
+        }
+        for b in 0..n {
+            if let Some(a) = (0..b).find(|&a| decided[a][b].is_none()) {
+                let (index, line) = declared[b];
+                let message = format!(
+                    "no rule says whether {} or {} wins",
+                    shapes[a].name, shapes[b].name
+                );
+                return Err(ParseError::line(line, message).on_line(index));
+            }
+        }
+        Ok(Rules { shapes, beats })
+    }
+
+    pub fn shapes(&self) -> &[ShapeRule] {
+        &self.shapes
+    }
+
+    /// The letters naming the shapes in the first column of the guide, in order.
+    pub fn opponent_letters(&self) -> String {
+        self.shapes.iter().map(|shape| shape.opponent).collect()
+    }
+
+    /// The letters naming the shapes in the second column of the guide, in order.
+    pub fn my_letters(&self) -> String {
+        self.shapes.iter().map(|shape| shape.me).collect()
+    }
+
+    /// What `me` gets against `opponent`.
+    pub fn outcome(&self, me: Shape, opponent: Shape) -> Outcome {
+        if me == opponent {
+            Outcome::Draw
+        } else if self.beats[me][opponent] {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        }
+    }
+
+    /// The first shape, in the order of the rules, that gets `outcome` against `opponent`.
+    pub fn against(&self, opponent: Shape, outcome: Outcome) -> Option<Shape> {
+        (0..self.shapes.len()).find(|&me| self.outcome(me, opponent) == outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::day2::rules::Rules;
+    use crate::solvers::day2::{Outcome, OUTCOMES};
+
+    const SPOCK: &str = "\
+        # rock-paper-scissors-lizard-Spock\n\
+        rock      A  V  1\n\
+        Spock     B  W  2\n\
+        paper     C  X  3\n\
+        lizard    D  Y  4\n\
+        scissors  E  Z  5\n\
+        \n\
+        scissors > paper\n\
+        paper > rock\n\
+        rock > lizard\n\
+        lizard > Spock\n\
+        Spock > scissors\n\
+        scissors > lizard\n\
+        lizard > paper\n\
+        paper > Spock\n\
+        Spock > rock\n\
+        rock > scissors\n";
+
+    #[test]
+    fn it_plays_rock_paper_scissors_by_default() {
+        let rules = Rules::default();
+
+        assert_eq!(rules.opponent_letters(), "ABC");
+        assert_eq!(rules.my_letters(), "XYZ");
+        assert_eq!(rules.outcome(1, 0), Outcome::Win);
+        assert_eq!(rules.outcome(0, 1), Outcome::Loss);
+        assert_eq!(rules.outcome(2, 2), Outcome::Draw);
+        assert_eq!(rules.outcome(0, 2), Outcome::Win);
+        assert_eq!(
+            Rules::parse(
+                "rock A X 1\npaper B Y 2\nscissors C Z 3\n\
+                 paper > rock\nrock > scissors\nscissors > paper\n"
+            ),
+            Ok(rules)
+        );
+    }
+
+    #[test]
+    fn it_reads_rock_paper_scissors_lizard_spock() {
+        let rules = Rules::parse(SPOCK).unwrap();
+        let shapes = rules.shapes().to_vec();
+
+        assert_eq!(shapes.len(), 5);
+        assert_eq!(rules.outcome(0, 3), Outcome::Win);
+        assert_eq!(rules.outcome(1, 3), Outcome::Loss);
+        // listed in this order, every shape beats the two before it
+        assert_eq!(rules, Rules::cyclic(shapes));
+        for opponent in 0..5 {
+            for outcome in OUTCOMES {
+                let me = rules.against(opponent, outcome).unwrap();
+                assert_eq!(rules.outcome(me, opponent), outcome);
+            }
+        }
+    }
+
+    #[test]
+    fn it_rejects_malformed_rules() {
+        let error = |content: &str| {
+            let error = Rules::parse(content).unwrap_err();
+            (error.line, error.column, error.message)
+        };
+
+        assert_eq!(
+            error("rock A X one\npaper B Y 2\n"),
+            (1, 10, "expected a score".to_string())
+        );
+        assert_eq!(
+            error("rock A X 1\npaper A Y 2\n"),
+            (
+                2,
+                1,
+                "repeats the name or a letter of the shape on line 1".to_string()
+            )
+        );
+        assert_eq!(
+            error("rock A X 1\npaper B Y 2\npaper > stone\n"),
+            (3, 9, "unknown shape".to_string())
+        );
+        assert_eq!(
+            error("rock A X 1\npaper B Y 2\npaper > rock\nrock > paper\n"),
+            (
+                4,
+                1,
+                "line 3 already decides between these shapes".to_string()
+            )
+        );
+        assert_eq!(
+            error("rock A X 1\npaper B Y 2\nscissors C Z 3\npaper > rock\n"),
+            (
+                3,
+                1,
+                "no rule says whether rock or scissors wins".to_string()
+            )
+        );
+        assert!(Rules::parse("rock A X 1\n").is_err());
+        assert!(Rules::parse("rock beats scissors\n").is_err());
+    }
+}
@@ -1,68 +1,199 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::solvers::Solver;
 use crate::Part;
-use std::collections::HashSet;
+use std::num::NonZeroUsize;
 
-pub fn solve(part: Part, lines: Vec<String>) -> String {
-    let scores: Vec<u32> = match part {
-        Part::A => lines
+/// Number of elves in a group when none is configured.
+const GROUP_SIZE: usize = 3;
+
+#[derive(Default)]
+pub struct Day3 {
+    /// Number of rucksacks sharing a badge instead of three.
+    pub group_size: Option<NonZeroUsize>,
+}
+
+impl Solver for Day3 {
+    type Input = Vec<Rucksack>;
+
+    fn parse(&self, lines: Vec<String>) -> Result<Vec<Rucksack>, ParseError> {
+        lines
             .into_iter()
-            .map(split_in_compartments)
-            .map(found_common_element)
-            .map(convert_to_priority)
-            .collect(),
-        Part::B => found_badges(lines)
+            .enumerate()
+            .map(|(index, line)| Rucksack::parse(line).map_err(|e| e.on_line(index)))
+            .collect()
+    }
+
+    fn part_a(&self, rucksacks: &Vec<Rucksack>) -> Result<Answer, SolveError> {
+        let result: u32 = found_shared_items(rucksacks)?
+            .into_iter()
+            .map(|shared| convert_to_priority(shared.item))
+            .sum();
+        Ok(Answer::from(result))
+    }
+
+    fn part_b(&self, rucksacks: &Vec<Rucksack>) -> Result<Answer, SolveError> {
+        let result: u32 = found_badges(rucksacks, self.group_size())?
             .into_iter()
             .map(convert_to_priority)
-            .collect(),
-    };
+            .sum();
+        Ok(Answer::from(result))
+    }
 
-    let result: u32 = scores.iter().sum();
-    String::from(format!("{}", result))
+    fn explain(&self, rucksacks: &Vec<Rucksack>, part: Part) -> Result<Option<String>, SolveError> {
+        let mut total = 0;
+        let explanation = match part {
+            Part::A => {
+                let mut lines = vec![format!(
+                    "{:>8}  {:<4}  {:>8}  {:>6}  COMPARTMENTS",
+                    "RUCKSACK", "ITEM", "PRIORITY", "TOTAL"
+                )];
+                for (index, shared) in found_shared_items(rucksacks)?.into_iter().enumerate() {
+                    let (first, second) = shared.compartments;
+                    let priority = convert_to_priority(shared.item);
+                    total += priority;
+                    lines.push(format!(
+                        "{:>8}  {:<4}  {:>8}  {:>6}  {} {}",
+                        index + 1,
+                        shared.item,
+                        priority,
+                        total,
+                        first,
+                        second
+                    ));
+                }
+                lines
+            }
+            Part::B => {
+                let size = self.group_size();
+                let mut lines = vec![format!(
+                    "{:>5}  {:<9}  {:<5}  {:>8}  {:>6}",
+                    "GROUP", "RUCKSACKS", "BADGE", "PRIORITY", "TOTAL"
+                )];
+                for (index, badge) in found_badges(rucksacks, size)?.into_iter().enumerate() {
+                    let priority = convert_to_priority(badge);
+                    total += priority;
+                    lines.push(format!(
+                        "{:>5}  {:<9}  {:<5}  {:>8}  {:>6}",
+                        index + 1,
+                        format!("{}-{}", index * size + 1, (index + 1) * size),
+                        badge,
+                        priority,
+                        total
+                    ));
+                }
+                lines
+            }
+        };
+        Ok(Some(explanation.join("\n") + "\n"))
+    }
 }
 
-fn found_badges(rucksacks: Vec<String>) -> Vec<char> {
-    let mut badges: Vec<char> = Vec::new();
-    let mut current_badge: Vec<char> = Vec::new();
-    for rucksack in rucksacks {
-        if current_badge.is_empty() {
-            rucksack.chars().for_each(|e| {
-                if !current_badge.contains(&e) {
-                    current_badge.push(e);
-                }
-            });
-        } else {
-            let prev_elements = current_badge.clone();
-            current_badge = Vec::new();
-            rucksack
-                .chars()
-                .filter(|e| prev_elements.contains(e))
-                .for_each(|e| {
-                    if !current_badge.contains(&e) {
-                        current_badge.push(e);
-                    }
-                })
+impl Day3 {
+    fn group_size(&self) -> usize {
+        self.group_size.map_or(GROUP_SIZE, NonZeroUsize::get)
+    }
+}
+
+/// The items of a rucksack, split evenly between its two compartments.
+#[derive(Debug, PartialEq)]
+pub struct Rucksack {
+    items: String,
+}
+
+impl Rucksack {
+    fn parse(items: String) -> Result<Rucksack, ParseError> {
+        if let Some((position, c)) = items.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::at(
+                &items,
+                &items[position..position + c.len_utf8()],
+                "expected an item between a-z or A-Z",
+            ));
         }
-        if current_badge.len() == 1 {
-            badges.push(*current_badge.first().unwrap());
-            current_badge = Vec::new();
+        if !items.len().is_multiple_of(2) {
+            return Err(ParseError::line(
+                &items,
+                "an odd number of items cannot be split in two compartments",
+            ));
         }
+        Ok(Rucksack { items })
     }
-    badges
+
+    fn compartments(&self) -> (&str, &str) {
+        self.items.split_at(self.items.len() / 2)
+    }
+}
+
+/// The item a rucksack holds in both of its compartments.
+struct SharedItem<'a> {
+    compartments: (&'a str, &'a str),
+    item: char,
 }
 
-fn split_in_compartments(rucksacks_content: String) -> (String, String) {
-    let split_index = rucksacks_content.len() / 2;
-    let x = rucksacks_content.split_at(split_index);
-    (x.0.to_string(), x.1.to_string())
+/// Finds the item packed in both compartments of every rucksack.
+fn found_shared_items(rucksacks: &[Rucksack]) -> Result<Vec<SharedItem<'_>>, SolveError> {
+    rucksacks
+        .iter()
+        .enumerate()
+        .map(|(index, rucksack)| {
+            let compartments = rucksack.compartments();
+            let item = found_common_element(compartments).map_err(|problem| {
+                SolveError::no_answer(format!("rucksack {} {}", index + 1, problem))
+            })?;
+            Ok(SharedItem { compartments, item })
+        })
+        .collect()
 }
 
-fn found_common_element(compartment_elements: (String, String)) -> char {
-    let set: HashSet<char> = compartment_elements.0.chars().collect();
-    let commons: Vec<char> = compartment_elements
-        .1
-        .chars()
-        .filter(|c| set.contains(c))
-        .collect();
-    commons.first().unwrap().clone()
+/// Splits the rucksacks in groups of `size` and finds the one item that every rucksack of a group
+/// holds.
+fn found_badges(rucksacks: &[Rucksack], size: usize) -> Result<Vec<char>, SolveError> {
+    let left_over = rucksacks.len() % size;
+    if left_over != 0 {
+        return Err(SolveError::no_answer(format!(
+            "group {} has only {} of {} rucksacks",
+            rucksacks.len() / size + 1,
+            left_over,
+            size
+        )));
+    }
+    rucksacks
+        .chunks(size)
+        .enumerate()
+        .map(|(index, group)| {
+            let common = group
+                .iter()
+                .map(|rucksack| ItemSet::from(rucksack.items.as_str()))
+                .reduce(ItemSet::intersection)
+                .unwrap_or_default();
+            single_item(common).map_err(|problem| {
+                SolveError::no_answer(format!(
+                    "group {} (rucksacks {} to {}) {}",
+                    index + 1,
+                    index * size + 1,
+                    (index + 1) * size,
+                    problem
+                ))
+            })
+        })
+        .collect()
+}
+
+fn found_common_element(compartments: (&str, &str)) -> Result<char, String> {
+    single_item(ItemSet::from(compartments.0).intersection(ItemSet::from(compartments.1)))
+}
+
+/// The only item of `common`, or what keeps it from being the only one.
+fn single_item(common: ItemSet) -> Result<char, String> {
+    match (common.iter().next(), common.len()) {
+        (Some(item), 1) => Ok(item),
+        (None, _) => Err("has no item in common".to_string()),
+        _ => Err(format!(
+            "has {} items in common: {}",
+            common.len(),
+            common.iter().collect::<String>()
+        )),
+    }
 }
 
 fn convert_to_priority(element: char) -> u32 {
@@ -74,11 +205,70 @@ fn convert_to_priority(element: char) -> u32 {
     }
 }
 
+/// The item of a priority, the reverse of [`convert_to_priority`].
+fn convert_to_item(priority: u32) -> char {
+    let i = if priority <= 26 {
+        priority + 96
+    } else {
+        priority + 38
+    };
+    char::from_u32(i).unwrap_or('?')
+}
+
+/// A set of items with one bit per priority, so that sets combine with a single bitwise operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    fn of(item: char) -> ItemSet {
+        ItemSet(1 << convert_to_priority(item))
+    }
+
+    fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// The items of the set, by increasing priority.
+    fn iter(self) -> impl Iterator<Item = char> {
+        (1..=52)
+            .filter(move |priority| self.0 & (1 << priority) != 0)
+            .map(convert_to_item)
+    }
+}
+
+impl From<&str> for ItemSet {
+    fn from(items: &str) -> ItemSet {
+        items
+            .chars()
+            .fold(ItemSet::default(), |set, item| set.union(ItemSet::of(item)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::solvers::day3::{found_badges, found_common_element, solve, split_in_compartments};
+    use crate::answer::Answer;
+    use crate::solvers::day3::{
+        convert_to_item, convert_to_priority, found_badges, found_common_element, Day3, ItemSet,
+        Rucksack,
+    };
+    use crate::solvers::{Puzzle, Solver};
     use crate::Part;
 
+    fn rucksacks(lines: &[&str]) -> Vec<Rucksack> {
+        lines
+            .iter()
+            .map(|line| Rucksack::parse(line.to_string()).unwrap())
+            .collect()
+    }
+
     #[test]
     fn solve_example_part_a() {
         let lines = vec![
@@ -89,8 +279,8 @@ mod tests {
             "ttgJtRGJQctTZtZT".to_string(),
             "CrZsJsPPZsGzwwsLwLmpwMDw".to_string(),
         ];
-        let result = solve(Part::A, lines);
-        assert_eq!(result, "157");
+        let result = Day3::default().solve(Part::A, lines);
+        assert_eq!(result, Ok(Answer::Integer(157)));
     }
 
     #[test]
@@ -103,46 +293,133 @@ mod tests {
             "ttgJtRGJQctTZtZT".to_string(),
             "CrZsJsPPZsGzwwsLwLmpwMDw".to_string(),
         ];
-        let result = solve(Part::B, lines);
-        assert_eq!(result, "70");
+        let result = Day3::default().solve(Part::B, lines);
+        assert_eq!(result, Ok(Answer::Integer(70)));
+    }
+
+    #[test]
+    fn it_rejects_items_that_are_not_letters() {
+        let lines = vec!["vJrwpWtw".to_string(), "jqHR-qRj".to_string()];
+        let error = Day3::default().parse(lines).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+
+        let error = Day3::default().parse(vec!["abéb".to_string()]).unwrap_err();
+        assert_eq!((error.line, error.column, error.width), (1, 3, 1));
     }
 
     #[test]
     fn it_split_a_string_in_half() {
-        let result = split_in_compartments(String::from("AAAABBBB"));
+        let rucksack = Rucksack::parse(String::from("AAAABBBB")).unwrap();
 
-        assert_eq!(result, ("AAAA".to_string(), "BBBB".to_string()));
+        assert_eq!(rucksack.compartments(), ("AAAA", "BBBB"));
+        assert!(Rucksack::parse(String::from("AAAABBB")).is_err());
     }
 
     #[test]
     fn it_found_common_element() {
-        let result = found_common_element(("AxAA".to_string(), "BBBx".to_string()));
+        let result = found_common_element(("AxAA", "BBBx"));
 
-        assert_eq!(result, 'x');
+        assert_eq!(result, Ok('x'));
     }
 
     #[test]
     fn it_found_badges_in_single_group() {
-        let lines = vec![
-            "vJrwpWtwJgWrhcsFMMfFFhFp".to_string(),
-            "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".to_string(),
-            "PmmdzqPrVvPwwTWBwg".to_string(),
-        ];
-        let result = found_badges(lines);
-        assert_eq!(result, vec!['r']);
+        let lines = rucksacks(&[
+            "vJrwpWtwJgWrhcsFMMfFFhFp",
+            "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+            "PmmdzqPrVvPwwTWBwg",
+        ]);
+        let result = found_badges(&lines, 3);
+        assert_eq!(result, Ok(vec!['r']));
     }
 
     #[test]
     fn it_found_badges() {
+        let lines = rucksacks(&[
+            "vJrwpWtwJgWrhcsFMMfFFhFp",
+            "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+            "PmmdzqPrVvPwwTWBwg",
+            "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+            "ttgJtRGJQctTZtZT",
+            "CrZsJsPPZsGzwwsLwLmpwMDw",
+        ]);
+        let result = found_badges(&lines, 3);
+        assert_eq!(result, Ok(vec!['r', 'Z']));
+    }
+
+    #[test]
+    fn it_converts_between_items_and_priorities() {
+        for item in ('a'..='z').chain('A'..='Z') {
+            assert_eq!(convert_to_item(convert_to_priority(item)), item);
+        }
+        assert_eq!(convert_to_priority('p'), 16);
+        assert_eq!(convert_to_priority('L'), 38);
+    }
+
+    #[test]
+    fn it_combines_item_sets() {
+        let first = ItemSet::from("abZa");
+        let second = ItemSet::from("bcZ");
+
+        assert_eq!(first.len(), 3);
+        assert_eq!(first.intersection(second).iter().collect::<String>(), "bZ");
+        assert_eq!(first.union(second).iter().collect::<String>(), "abcZ");
+        assert_eq!(ItemSet::from("a").intersection(ItemSet::from("A")).len(), 0);
+    }
+
+    #[test]
+    fn it_checks_every_group() {
+        let lines = rucksacks(&["ab", "bc", "bd"]);
+        assert_eq!(found_badges(&lines, 3), Ok(vec!['b']));
+        assert_eq!(
+            found_badges(&lines[..1], 1).unwrap_err().to_string(),
+            "group 1 (rucksacks 1 to 1) has 2 items in common: ab"
+        );
+        assert_eq!(
+            found_badges(&lines, 2).unwrap_err().to_string(),
+            "group 2 has only 1 of 2 rucksacks"
+        );
+
+        let lines = rucksacks(&["ab", "cd", "ac"]);
+        assert_eq!(
+            found_badges(&lines, 3).unwrap_err().to_string(),
+            "group 1 (rucksacks 1 to 3) has no item in common"
+        );
+    }
+
+    #[test]
+    fn it_rejects_rucksacks_of_odd_length() {
+        let lines = vec!["abcb".to_string(), "abcab".to_string()];
+        let error = Day3::default().solve(Part::A, lines).unwrap_err();
+
+        assert!(error.to_string().starts_with("an odd number of items"));
+        assert!(error.to_string().contains("2 | abcab"));
+    }
+
+    #[test]
+    fn it_explains_every_rucksack_and_group() {
         let lines = vec![
             "vJrwpWtwJgWrhcsFMMfFFhFp".to_string(),
             "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".to_string(),
             "PmmdzqPrVvPwwTWBwg".to_string(),
-            "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn".to_string(),
-            "ttgJtRGJQctTZtZT".to_string(),
-            "CrZsJsPPZsGzwwsLwLmpwMDw".to_string(),
         ];
-        let result = found_badges(lines);
-        assert_eq!(result, vec!['r', 'Z']);
+        assert_eq!(
+            Puzzle::explain(&Day3::default(), Part::A, lines.clone()),
+            Ok(Some(
+                "RUCKSACK  ITEM  PRIORITY   TOTAL  COMPARTMENTS\n\
+                 \x20      1  p           16      16  vJrwpWtwJgWr hcsFMMfFFhFp\n\
+                 \x20      2  L           38      54  jqHRNqRjqzjGDLGL rsFMfFZSrLrFZsSL\n\
+                 \x20      3  P           42      96  PmmdzqPrV vPwwTWBwg\n"
+                    .to_string()
+            ))
+        );
+        assert_eq!(
+            Puzzle::explain(&Day3::default(), Part::B, lines),
+            Ok(Some(
+                "GROUP  RUCKSACKS  BADGE  PRIORITY   TOTAL\n\
+                 \x20   1  1-3        r            18      18\n"
+                    .to_string()
+            ))
+        );
     }
 }
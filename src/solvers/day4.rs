@@ -1,59 +1,130 @@
-use crate::Part;
-
-pub fn solve(part: Part, lines: Vec<String>) -> String {
-    let result = lines
-        .into_iter()
-        .map(split_to_pair)
-        .map(|p| (expand_to_sections(p.0), expand_to_sections(p.1)))
-        .map(|p| match part {
-            Part::A => have_complete_overlap(p),
-            Part::B => have_partial_overlap(p),
-        })
-        .filter(|&e| e == true)
-        .count()
-        .to_string();
-
-    String::from(format!("{}", result))
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::interval::{Coverage, Interval, IntervalSet};
+use crate::solvers::Solver;
+
+#[derive(Default)]
+pub struct Day4 {
+    /// Sections whose number of elves the report gives.
+    pub sections: Vec<i64>,
 }
 
-fn split_to_pair(line: String) -> (String, String) {
-    match line.split_once(',') {
-        None => panic!("invalid value"),
-        Some(el) => (el.0.to_string(), el.1.to_string()),
+impl Solver for Day4 {
+    type Input = Vec<(Interval, Interval)>;
+
+    fn parse(&self, lines: Vec<String>) -> Result<Vec<(Interval, Interval)>, ParseError> {
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let (first, second) = split_to_pair(line.clone()).map_err(|e| e.on_line(index))?;
+                let second_offset = first.chars().count() + 1;
+                Ok((
+                    parse_assignment(first).map_err(|e| e.within(&line, 0).on_line(index))?,
+                    parse_assignment(second)
+                        .map_err(|e| e.within(&line, second_offset).on_line(index))?,
+                ))
+            })
+            .collect()
+    }
+
+    fn part_a(&self, pairs: &Vec<(Interval, Interval)>) -> Result<Answer, SolveError> {
+        let result = pairs
+            .iter()
+            .filter(|(first, second)| first.contains(second) || second.contains(first))
+            .count();
+        Ok(Answer::from(result))
+    }
+
+    fn part_b(&self, pairs: &Vec<(Interval, Interval)>) -> Result<Answer, SolveError> {
+        let result = pairs
+            .iter()
+            .filter(|(first, second)| first.overlaps(second))
+            .count();
+        Ok(Answer::from(result))
+    }
+
+    fn report(&self, pairs: &Vec<(Interval, Interval)>) -> Result<Option<String>, SolveError> {
+        Ok(Some(camp_report(pairs, &self.sections)))
     }
 }
 
-fn expand_to_sections(assignment: String) -> Vec<u32> {
-    if let Some((min, max)) = assignment.split_once('-') {
-        let start: u32 = min.parse::<u32>().unwrap();
-        let end: u32 = max.parse::<u32>().unwrap() + 1;
-        let mut vec = Vec::new();
-        for i in start..end {
-            vec.push(i);
-        }
-        return vec;
-    } else {
-        return vec![];
+/// Describes how the assignments of every elf in the camp cover the sections together.
+fn camp_report(pairs: &[(Interval, Interval)], sections: &[i64]) -> String {
+    let assignments: Vec<Interval> = pairs.iter().flat_map(|(a, b)| [*a, *b]).collect();
+    let covered: IntervalSet = assignments.iter().copied().collect();
+    let coverage = Coverage::new(&assignments);
+    let list = |intervals: &[Interval]| match intervals {
+        [] => "none".to_string(),
+        _ => intervals
+            .iter()
+            .map(Interval::to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let mut report = format!(
+        "{} assignments cover {} sections: {}\n",
+        assignments.len(),
+        covered.len(),
+        list(covered.intervals())
+    );
+    report.push_str(&format!("gaps: {}\n", list(&covered.gaps())));
+    if let Some((elves, intervals)) = coverage.max_overlap() {
+        report.push_str(&format!(
+            "most assigned: {} elves on {}\n",
+            elves,
+            list(&intervals)
+        ));
+    }
+    report.push_str(&format!("{:>11}  ELVES\n", "SECTIONS"));
+    for (interval, elves) in coverage.counts() {
+        report.push_str(&format!("{:>11}  {}\n", interval.to_string(), elves));
     }
+    for section in sections {
+        report.push_str(&format!(
+            "section {}: {} elves\n",
+            section,
+            coverage.count_at(*section)
+        ));
+    }
+    report
 }
 
-fn have_complete_overlap(pair_assignment: (Vec<u32>, Vec<u32>)) -> bool {
-    (pair_assignment.0.first().unwrap() <= pair_assignment.1.first().unwrap()
-        && pair_assignment.0.last().unwrap() >= pair_assignment.1.last().unwrap())
-        || (pair_assignment.0.first().unwrap() >= pair_assignment.1.first().unwrap()
-            && pair_assignment.0.last().unwrap() <= pair_assignment.1.last().unwrap())
+fn split_to_pair(line: String) -> Result<(String, String), ParseError> {
+    match line.split_once(',') {
+        None => Err(ParseError::line(
+            &line,
+            "expected two assignments separated by a comma",
+        )),
+        Some(el) => Ok((el.0.to_string(), el.1.to_string())),
+    }
 }
 
-fn have_partial_overlap(pair_assignment: (Vec<u32>, Vec<u32>)) -> bool {
-    pair_assignment.0.first().unwrap() <= pair_assignment.1.last().unwrap()
-        && pair_assignment.0.last().unwrap() >= pair_assignment.1.first().unwrap()
+fn parse_assignment(assignment: String) -> Result<Interval, ParseError> {
+    let Some((min, max)) = assignment.split_once('-') else {
+        return Err(ParseError::line(
+            &assignment,
+            "expected a range of sections like 2-4",
+        ));
+    };
+    let parse = |section: &str| {
+        section
+            .parse::<u32>()
+            .map_err(|_| ParseError::at(&assignment, section, "expected a section number"))
+    };
+    let start = parse(min)?;
+    let end = parse(max)?;
+    Interval::new(start.into(), end.into())
+        .ok_or_else(|| ParseError::line(&assignment, "range of sections ends before it starts"))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solvers::day4::{
-        expand_to_sections, have_complete_overlap, have_partial_overlap, solve, split_to_pair,
-    };
+    use crate::answer::Answer;
+    use crate::interval::Interval;
+    use crate::solvers::day4::{camp_report, parse_assignment, split_to_pair, Day4};
+    use crate::solvers::{Puzzle, Solver};
     use crate::Part;
 
     #[test]
@@ -66,8 +137,8 @@ mod tests {
             "6-6,4-6".to_string(),
             "2-6,4-8".to_string(),
         ];
-        let result = solve(Part::A, lines);
-        assert_eq!(result, "2");
+        let result = Day4::default().solve(Part::A, lines);
+        assert_eq!(result, Ok(Answer::Integer(2)));
     }
 
     #[test]
@@ -80,59 +151,58 @@ mod tests {
             "6-6,4-6".to_string(),
             "2-6,4-8".to_string(),
         ];
-        let result = solve(Part::B, lines);
-        assert_eq!(result, "4");
+        let result = Day4::default().solve(Part::B, lines);
+        assert_eq!(result, Ok(Answer::Integer(4)));
     }
 
     #[test]
     fn it_split_to_pair_assignment() {
-        let result = split_to_pair("2-4,6-8".to_string());
+        let result = split_to_pair("2-4,6-8".to_string()).unwrap();
 
         assert_eq!(result, ("2-4".to_string(), "6-8".to_string()));
     }
 
     #[test]
-    fn it_expand_assignment_to_sections() {
-        let result = expand_to_sections("2-4".to_string());
+    fn it_parse_assignment_to_interval() {
+        let result = parse_assignment("1-4000000000".to_string());
 
-        assert_eq!(result, vec![2, 3, 4]);
+        assert_eq!(result, Ok(Interval::new(1, 4_000_000_000).unwrap()));
     }
 
     #[test]
-    fn it_returns_if_two_ranges_have_a_complete_overlap() {
-        assert_eq!(have_complete_overlap((vec![2], vec![2])), true);
-        assert_eq!(have_complete_overlap((vec![2, 3, 4], vec![2])), true);
-        assert_eq!(have_complete_overlap((vec![2, 3, 4], vec![3])), true);
-        assert_eq!(have_complete_overlap((vec![2, 3, 4], vec![4])), true);
-        assert_eq!(have_complete_overlap((vec![2, 3, 4], vec![2, 3])), true);
-        assert_eq!(have_complete_overlap((vec![2, 3, 4], vec![3, 4])), true);
-        assert_eq!(have_complete_overlap((vec![2], vec![2, 3, 4])), true);
-        assert_eq!(have_complete_overlap((vec![3], vec![2, 3, 4])), true);
-        assert_eq!(have_complete_overlap((vec![4], vec![2, 3, 4])), true);
-        assert_eq!(have_complete_overlap((vec![2, 3], vec![2, 3, 4])), true);
-        assert_eq!(have_complete_overlap((vec![3, 4], vec![2, 3, 4])), true);
-
-        assert_eq!(have_complete_overlap((vec![2], vec![3])), false);
-        assert_eq!(have_complete_overlap((vec![2, 3, 4], vec![1, 2])), false);
-        assert_eq!(have_complete_overlap((vec![2, 3, 4], vec![4, 5])), false);
+    fn it_rejects_malformed_assignments() {
+        assert!(split_to_pair("2-4".to_string()).is_err());
+        assert!(parse_assignment("4-2".to_string()).is_err());
+
+        let error = Day4::default()
+            .parse(vec!["2-4,6-8".to_string(), "2-4,6-y".to_string()])
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.text, "2-4,6-y");
     }
 
     #[test]
-    fn it_returns_if_two_ranges_have_a_partial_overlap() {
-        assert_eq!(have_partial_overlap((vec![2], vec![2])), true);
-        assert_eq!(have_partial_overlap((vec![2, 3, 4], vec![2])), true);
-        assert_eq!(have_partial_overlap((vec![2, 3, 4], vec![3])), true);
-        assert_eq!(have_partial_overlap((vec![2, 3, 4], vec![4])), true);
-        assert_eq!(have_partial_overlap((vec![2, 3, 4], vec![2, 3])), true);
-        assert_eq!(have_partial_overlap((vec![2, 3, 4], vec![3, 4])), true);
-        assert_eq!(have_partial_overlap((vec![2], vec![2, 3, 4])), true);
-        assert_eq!(have_partial_overlap((vec![3], vec![2, 3, 4])), true);
-        assert_eq!(have_partial_overlap((vec![4], vec![2, 3, 4])), true);
-        assert_eq!(have_partial_overlap((vec![2, 3], vec![2, 3, 4])), true);
-        assert_eq!(have_partial_overlap((vec![3, 4], vec![2, 3, 4])), true);
-
-        assert_eq!(have_partial_overlap((vec![2], vec![3])), false);
-        assert_eq!(have_partial_overlap((vec![2, 3, 4], vec![1, 2])), true);
-        assert_eq!(have_partial_overlap((vec![2, 3, 4], vec![4, 5])), true);
+    fn it_reports_coverage_of_the_whole_camp() {
+        let pairs = Day4::default()
+            .parse(vec![
+                "2-4,6-8".to_string(),
+                "2-3,4-5".to_string(),
+                "11-12,12-12".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            camp_report(&pairs, &[4, 10]),
+            "6 assignments cover 9 sections: 2-8, 11-12\n\
+             gaps: 9-10\n\
+             most assigned: 2 elves on 2-4, 12-12\n\
+             \x20  SECTIONS  ELVES\n\
+             \x20       2-4  2\n\
+             \x20       5-8  1\n\
+             \x20     11-11  1\n\
+             \x20     12-12  2\n\
+             section 4: 2 elves\n\
+             section 10: 0 elves\n"
+        );
     }
 }
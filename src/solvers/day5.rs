@@ -1,74 +1,223 @@
-use crate::Part;
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::solvers::Solver;
 
-pub fn solve(part: Part, stacks: Vec<Vec<char>>, lines: Vec<String>) -> String {
-    let instructions: Vec<Instruction> = lines
-        .into_iter()
-        .filter(|l| l.starts_with("move"))
-        .map(Instruction::from)
-        .collect();
+pub struct Day5;
+
+/// The starting stacks, bottom crate first, and the moves of the crane.
+#[derive(Debug, PartialEq)]
+pub struct Supplies {
+    stacks: Vec<Vec<char>>,
+    instructions: Vec<Instruction>,
+}
 
-    let result = match part {
-        Part::A => apply_instruction_a(stacks, instructions),
-        Part::B => apply_instruction_b(stacks, instructions),
+impl Solver for Day5 {
+    type Input = Supplies;
+
+    fn parse(&self, lines: Vec<String>) -> Result<Supplies, ParseError> {
+        let blank = lines.iter().position(String::is_empty).ok_or_else(|| {
+            let first = lines.first().map_or("", String::as_str);
+            ParseError::line(
+                first,
+                "expected the drawing of the stacks, a blank line and the moves",
+            )
+            .on_line(0)
+        })?;
+        let stacks = parse_stacks(&lines[..blank])?;
+        let instructions = lines
+            .iter()
+            .enumerate()
+            .skip(blank + 1)
+            .filter(|(_, l)| !l.is_empty())
+            .map(|(index, l)| Instruction::parse(l, stacks.len()).map_err(|e| e.on_line(index)))
+            .collect::<Result<_, _>>()?;
+        Ok(Supplies {
+            stacks,
+            instructions,
+        })
+    }
+
+    fn part_a(&self, supplies: &Supplies) -> Result<Answer, SolveError> {
+        Ok(Answer::Text(get_last_elements(apply_instruction_a(
+            supplies.stacks.clone(),
+            supplies.instructions.clone(),
+        )?)))
+    }
+
+    fn part_b(&self, supplies: &Supplies) -> Result<Answer, SolveError> {
+        Ok(Answer::Text(get_last_elements(apply_instruction_b(
+            supplies.stacks.clone(),
+            supplies.instructions.clone(),
+        )?)))
+    }
+}
+
+/// Reads the drawing of the starting stacks, whose last line numbers them:
+///
+/// ```text
+///     [D]
+/// [N] [C]
+/// [Z] [M] [P]
+///  1   2   3
+/// ```
+///
+/// Rows may stop right after their last crate.
+fn parse_stacks(drawing: &[String]) -> Result<Vec<Vec<char>>, ParseError> {
+    let Some((header, rows)) = drawing.split_last() else {
+        return Err(ParseError::line("", "expected the drawing of the stacks").on_line(0));
     };
-    String::from(get_last_elements(result))
+    let header_index = rows.len();
+    let mut columns = Vec::new();
+    for (position, number) in header.split_whitespace().enumerate() {
+        if number.parse::<usize>() != Ok(position + 1) {
+            return Err(ParseError::at(
+                header,
+                number,
+                format!("expected stack number {}", position + 1),
+            )
+            .on_line(header_index));
+        }
+        columns.push(number.as_ptr() as usize - header.as_ptr() as usize);
+    }
+    if columns.is_empty() || !header.is_ascii() {
+        return Err(
+            ParseError::line(header, "expected the numbers of the stacks").on_line(header_index),
+        );
+    }
+
+    let mut stacks = vec![Vec::new(); columns.len()];
+    for (index, row) in rows.iter().enumerate().rev() {
+        let error = |column: usize, message: &str| {
+            let end = (column + 2).min(row.len());
+            let start = column.saturating_sub(1).min(end);
+            let token = row.get(start..end).filter(|t| !t.is_empty()).unwrap_or(row);
+            ParseError::at(row, token, message).on_line(index)
+        };
+        if !row.is_ascii() {
+            return Err(ParseError::line(row, "expected crates like [A]").on_line(index));
+        }
+        let bytes = row.as_bytes();
+        let mut covered = vec![false; bytes.len()];
+        for (stack, &column) in stacks.iter_mut().zip(columns.iter()) {
+            let start = column.saturating_sub(1).min(bytes.len());
+            let cell = &bytes[start..(column + 2).min(bytes.len())];
+            match cell {
+                [b'[', letter, b']'] if letter.is_ascii_alphabetic() => {
+                    if stack.len() + index + 1 != rows.len() {
+                        return Err(error(column, "crate floating above an empty slot"));
+                    }
+                    stack.push(*letter as char);
+                }
+                _ if cell.iter().all(|b| *b == b' ') => {}
+                _ => return Err(error(column, "expected a crate like [A] or nothing")),
+            }
+            for covered in covered.iter_mut().skip(column.saturating_sub(1)).take(3) {
+                *covered = true;
+            }
+        }
+        if let Some(position) = (0..bytes.len()).find(|&i| !covered[i] && bytes[i] != b' ') {
+            return Err(ParseError::at(
+                row,
+                &row[position..position + 1],
+                "unexpected text between the stacks",
+            )
+            .on_line(index));
+        }
+    }
+    Ok(stacks)
 }
 
-#[derive(PartialEq, Debug)]
-struct Instruction {
+#[derive(PartialEq, Debug, Clone)]
+pub struct Instruction {
     quantity: u32,
     from: usize,
     to: usize,
 }
 
 impl Instruction {
-    fn from(line: String) -> Instruction {
-        let mut splited = line.split(' ');
-        let _ = splited.next();
-        let quantity = splited.next();
-        let _ = splited.next();
-        let from = splited.next();
-        let _ = splited.next();
-        let to = splited.next();
-
-        Instruction {
-            quantity: quantity.unwrap().parse::<u32>().unwrap(),
-            from: from.unwrap().parse::<usize>().unwrap(),
-            to: to.unwrap().parse::<usize>().unwrap(),
-        }
+    /// Reads `move <quantity> from <stack> to <stack>`, with stacks numbered from 1 to `stacks`.
+    fn parse(line: &str, stacks: usize) -> Result<Instruction, ParseError> {
+        let words: Vec<&str> = line.split(' ').collect();
+        let ["move", quantity, "from", from, "to", to] = words[..] else {
+            return Err(ParseError::line(
+                line,
+                "expected an instruction like 'move 1 from 2 to 3'",
+            ));
+        };
+        let quantity = quantity
+            .parse::<u32>()
+            .map_err(|_| ParseError::at(line, quantity, "expected a number of crates"))?;
+        let stack = |word: &str| match word.parse::<usize>() {
+            Ok(n) if (1..=stacks).contains(&n) => Ok(n),
+            _ => Err(ParseError::at(
+                line,
+                word,
+                format!("expected a stack between 1 and {}", stacks),
+            )),
+        };
+
+        Ok(Instruction {
+            quantity,
+            from: stack(from)?,
+            to: stack(to)?,
+        })
     }
 }
 
-fn apply_instruction_a(stack: Vec<Vec<char>>, instructions: Vec<Instruction>) -> Vec<Vec<char>> {
-    let mut cur_stack = stack.clone();
+fn not_enough_crates(instruction: &Instruction, stack: &[char]) -> SolveError {
+    SolveError::no_answer(format!(
+        "cannot move {} crates from stack {} holding {}",
+        instruction.quantity,
+        instruction.from,
+        stack.len()
+    ))
+}
+
+fn apply_instruction_a(
+    stack: Vec<Vec<char>>,
+    instructions: Vec<Instruction>,
+) -> Result<Vec<Vec<char>>, SolveError> {
+    let mut cur_stack = stack;
     for instruction in instructions {
         for _ in 0..instruction.quantity {
             let x = cur_stack[instruction.from - 1].pop();
             match x {
-                None => {}
+                None => {
+                    return Err(not_enough_crates(
+                        &instruction,
+                        &cur_stack[instruction.from - 1],
+                    ))
+                }
                 Some(el) => cur_stack[instruction.to - 1].push(el),
             }
         }
     }
-    cur_stack
+    Ok(cur_stack)
 }
 
-fn apply_instruction_b(stack: Vec<Vec<char>>, instructions: Vec<Instruction>) -> Vec<Vec<char>> {
-    let mut cur_stack = stack.clone();
+fn apply_instruction_b(
+    stack: Vec<Vec<char>>,
+    instructions: Vec<Instruction>,
+) -> Result<Vec<Vec<char>>, SolveError> {
+    let mut cur_stack = stack;
     for instruction in instructions {
-        let new_len = cur_stack[instruction.from - 1].len() - instruction.quantity as usize;
+        let from = &cur_stack[instruction.from - 1];
+        let new_len = from
+            .len()
+            .checked_sub(instruction.quantity as usize)
+            .ok_or_else(|| not_enough_crates(&instruction, from))?;
         let x = cur_stack[instruction.from - 1].split_off(new_len);
         for removed_element in x {
             cur_stack[instruction.to - 1].push(removed_element);
         }
     }
-    cur_stack
+    Ok(cur_stack)
 }
 
 fn get_last_elements(stack: Vec<Vec<char>>) -> String {
     let x: Vec<char> = stack
         .into_iter()
-        .map(|e| e.clone().pop().unwrap())
+        .filter_map(|e| e.last().copied())
         .collect();
     let ex = x
         .into_iter()
@@ -78,40 +227,80 @@ fn get_last_elements(stack: Vec<Vec<char>>) -> String {
 
 #[cfg(test)]
 mod tests {
+    use crate::answer::Answer;
     use crate::solvers::day5::{
-        apply_instruction_a, apply_instruction_b, get_last_elements, solve, Instruction,
+        apply_instruction_a, apply_instruction_b, get_last_elements, parse_stacks, Day5,
+        Instruction,
     };
+    use crate::solvers::{Puzzle, Solver};
     use crate::Part;
 
-    #[test]
-    fn solve_example_part_a() {
-        let stacks = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
-        let instructions = vec![
+    fn example() -> Vec<String> {
+        vec![
+            "    [D]    ".to_string(),
+            "[N] [C]    ".to_string(),
+            "[Z] [M] [P]".to_string(),
+            " 1   2   3 ".to_string(),
+            "".to_string(),
             "move 1 from 2 to 1".to_string(),
             "move 3 from 1 to 3".to_string(),
             "move 2 from 2 to 1".to_string(),
             "move 1 from 1 to 2".to_string(),
-        ];
-        let result = solve(Part::A, stacks, instructions);
-        assert_eq!(result, "CMZ");
+        ]
+    }
+
+    #[test]
+    fn solve_example_part_a() {
+        let result = Day5.solve(Part::A, example());
+        assert_eq!(result, Ok(Answer::from("CMZ")));
     }
 
     #[test]
     fn solve_example_part_b() {
-        let stacks = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
-        let instructions = vec![
-            "move 1 from 2 to 1".to_string(),
-            "move 3 from 1 to 3".to_string(),
-            "move 2 from 2 to 1".to_string(),
-            "move 1 from 1 to 2".to_string(),
+        let result = Day5.solve(Part::B, example());
+        assert_eq!(result, Ok(Answer::from("MCD")));
+    }
+
+    #[test]
+    fn it_reads_the_drawing_of_the_stacks() {
+        let drawing = vec![
+            "    [D]".to_string(),
+            "[N] [C]".to_string(),
+            "[Z] [M] [P]".to_string(),
+            " 1   2   3".to_string(),
         ];
-        let result = solve(Part::B, stacks, instructions);
-        assert_eq!(result, "MCD");
+
+        assert_eq!(
+            parse_stacks(&drawing),
+            Ok(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']])
+        );
+    }
+
+    #[test]
+    fn it_rejects_malformed_drawings() {
+        let floating = vec![
+            "    [D]".to_string(),
+            "[N]".to_string(),
+            "[Z]     [P]".to_string(),
+            " 1   2   3".to_string(),
+        ];
+        let error = parse_stacks(&floating).unwrap_err();
+        assert_eq!((error.line, error.column, error.width), (1, 5, 3));
+
+        let unnumbered = vec!["[Z] [M]".to_string(), " 1   3".to_string()];
+        let error = parse_stacks(&unnumbered).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+
+        let stray = vec!["[Z] [M] x".to_string(), " 1   2".to_string()];
+        let error = parse_stacks(&stray).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 9));
+
+        assert!(Day5.parse(vec!["move 1 from 2 to 1".to_string()]).is_err());
     }
 
     #[test]
     fn it_translate_line_to_instruction() {
-        let instruction = Instruction::from("move 1 from 2 to 3".to_string());
+        let instruction = Instruction::parse("move 1 from 2 to 3", 3).unwrap();
 
         assert_eq!(
             instruction,
@@ -137,7 +326,7 @@ mod tests {
         );
 
         assert_eq!(
-            result,
+            result.unwrap(),
             vec![vec!['Z', 'N'], vec!['N', 'C'], vec!['P', 'D'],]
         )
     }
@@ -168,7 +357,7 @@ mod tests {
         );
 
         assert_eq!(
-            result,
+            result.unwrap(),
             vec![vec!['Z'], vec!['N', 'C', 'P', 'D', 'N'], vec![],]
         )
     }
@@ -187,7 +376,7 @@ mod tests {
         );
 
         assert_eq!(
-            result,
+            result.unwrap(),
             vec![vec!['Z', 'N'], vec!['N', 'C'], vec!['P', 'D'],]
         )
     }
@@ -218,11 +407,32 @@ mod tests {
         );
 
         assert_eq!(
-            result,
+            result.unwrap(),
             vec![vec!['Z'], vec!['N', 'C', 'N', 'P', 'D'], vec![],]
         )
     }
 
+    #[test]
+    fn it_rejects_malformed_instructions() {
+        assert!(Instruction::parse("move 1 from 2", 3).is_err());
+        assert!(Instruction::parse("move x from 2 to 3", 3).is_err());
+
+        let error = Instruction::parse("move 1 from 2 to 4", 3).unwrap_err();
+        assert_eq!(error.column, 18);
+    }
+
+    #[test]
+    fn it_refuses_to_move_more_crates_than_a_stack_holds() {
+        let instructions = vec![Instruction {
+            quantity: 3,
+            from: 1,
+            to: 2,
+        }];
+
+        assert!(apply_instruction_a(vec![vec!['Z', 'N'], vec![]], instructions.clone()).is_err());
+        assert!(apply_instruction_b(vec![vec!['Z', 'N'], vec![]], instructions).is_err());
+    }
+
     #[test]
     fn it_get_last_elements() {
         let stacks = vec![vec!['Z', 'N'], vec!['N', 'C', 'D'], vec!['P']];
@@ -1,153 +1,277 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::solvers::Solver;
 use crate::Part;
-use std::collections::HashSet;
-use std::hash::Hash;
-
-pub fn solve(part: Part, line: String) -> String {
-    let result = match part {
-        Part::A => find_marker(line),
-        Part::B => find_generic_marker(line, 14),
-    };
-    format!("{}", result)
+use detector::{first_marker, markers, read_marker, BYTE_VALUES};
+use std::io::{self, Read};
+use std::num::NonZeroUsize;
+
+mod detector;
+
+/// Length of the start-of-packet marker of part A.
+const PACKET: usize = 4;
+/// Length of the start-of-message marker of part B.
+const MESSAGE: usize = 14;
+
+#[derive(Default)]
+pub struct Day6 {
+    /// Marker length used by both parts instead of theirs.
+    pub window: Option<NonZeroUsize>,
 }
 
-fn find_marker(input: String) -> usize {
-    find_generic_marker(input, 4)
+impl Solver for Day6 {
+    type Input = String;
+
+    fn parse(&self, lines: Vec<String>) -> Result<String, ParseError> {
+        Ok(lines.into_iter().next().unwrap_or_default())
+    }
+
+    fn part_a(&self, line: &String) -> Result<Answer, SolveError> {
+        self.solve_with(line, Day6::default_window(Part::A))
+    }
+
+    fn part_b(&self, line: &String) -> Result<Answer, SolveError> {
+        self.solve_with(line, Day6::default_window(Part::B))
+    }
+
+    fn report(&self, line: &String) -> Result<Option<String>, SolveError> {
+        let windows = match self.window {
+            Some(_) => vec![self.window_or(PACKET)?],
+            None => vec![PACKET, MESSAGE],
+        };
+        let mut report = String::new();
+        for window in windows {
+            let positions: Vec<String> = markers(line.bytes(), window)
+                .map(|position| position.to_string())
+                .collect();
+            report.push_str(&format!(
+                "{} markers of {} distinct characters\n",
+                positions.len(),
+                window
+            ));
+            for row in positions.chunks(16) {
+                report.push_str(&format!("  {}\n", row.join(" ")));
+            }
+        }
+        Ok(Some(report))
+    }
+
+    fn streams(&self) -> bool {
+        true
+    }
+
+    fn stream(
+        &self,
+        reader: &mut dyn Read,
+        part: Part,
+    ) -> Option<io::Result<Result<Answer, SolveError>>> {
+        let window = match self.window_or(Day6::default_window(part)) {
+            Ok(window) => window,
+            Err(error) => return Some(Ok(Err(error))),
+        };
+        let marker = read_marker(reader, window)
+            .map(|marker| marker.map(Answer::from).ok_or_else(|| no_marker(window)));
+        Some(marker)
+    }
 }
 
-fn find_generic_marker(input: String, packet_length: usize) -> usize {
-    for i in packet_length..input.len() {
-        let mut mark = Vec::new();
-        for j in (1..packet_length + 1).rev() {
-            mark.push(input.chars().nth(i - j));
+impl Day6 {
+    fn solve_with(&self, line: &str, default: usize) -> Result<Answer, SolveError> {
+        let window = self.window_or(default)?;
+        first_marker(line.bytes(), window)
+            .map(Answer::from)
+            .ok_or_else(|| no_marker(window))
+    }
+
+    fn default_window(part: Part) -> usize {
+        match part {
+            Part::A => PACKET,
+            Part::B => MESSAGE,
         }
-        if has_unique_elements(mark) {
-            return i;
+    }
+
+    /// The configured window, or `default`. A window longer than the number of byte values can
+    /// never hold distinct bytes only, so it is refused before anything is allocated for it.
+    fn window_or(&self, default: usize) -> Result<usize, SolveError> {
+        match self.window.map_or(default, NonZeroUsize::get) {
+            window if window > BYTE_VALUES => Err(SolveError::no_answer(format!(
+                "no {} distinct characters in a row, there are only {} byte values",
+                window, BYTE_VALUES
+            ))),
+            window => Ok(window),
         }
     }
-    0
 }
 
-fn has_unique_elements<T>(iter: T) -> bool
-where
-    T: IntoIterator,
-    T::Item: Eq + Hash,
-{
-    let mut uniq = HashSet::new();
-    iter.into_iter().all(move |x| uniq.insert(x))
+fn no_marker(window: usize) -> SolveError {
+    SolveError::no_answer(format!("no {} distinct characters in a row", window))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solvers::day6::{find_generic_marker, find_marker, solve};
+    use crate::answer::Answer;
+    use crate::solvers::day6::{first_marker, Day6, MESSAGE, PACKET};
+    use crate::solvers::Puzzle;
     use crate::Part;
+    use std::num::NonZeroUsize;
 
     #[test]
     fn solve_examples_part_a() {
         assert_eq!(
-            solve(Part::A, "bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()),
-            "5"
+            Day6::default().solve(Part::A, vec!["bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()]),
+            Ok(Answer::Integer(5))
         );
         assert_eq!(
-            solve(Part::A, "nppdvjthqldpwncqszvftbrmjlhg".to_string()),
-            "6"
+            Day6::default().solve(Part::A, vec!["nppdvjthqldpwncqszvftbrmjlhg".to_string()]),
+            Ok(Answer::Integer(6))
         );
         assert_eq!(
-            solve(Part::A, "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string()),
-            "10"
+            Day6::default().solve(
+                Part::A,
+                vec!["nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string()]
+            ),
+            Ok(Answer::Integer(10))
         );
         assert_eq!(
-            solve(Part::A, "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string()),
-            "11"
+            Day6::default().solve(
+                Part::A,
+                vec!["zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string()]
+            ),
+            Ok(Answer::Integer(11))
         );
     }
 
     #[test]
     fn solve_examples_part_b() {
         assert_eq!(
-            solve(Part::B, "mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()),
-            "19"
+            Day6::default().solve(Part::B, vec!["mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()]),
+            Ok(Answer::Integer(19))
         );
         assert_eq!(
-            solve(Part::B, "bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()),
-            "23"
+            Day6::default().solve(Part::B, vec!["bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()]),
+            Ok(Answer::Integer(23))
         );
         assert_eq!(
-            solve(Part::B, "nppdvjthqldpwncqszvftbrmjlhg".to_string()),
-            "23"
+            Day6::default().solve(Part::B, vec!["nppdvjthqldpwncqszvftbrmjlhg".to_string()]),
+            Ok(Answer::Integer(23))
         );
         assert_eq!(
-            solve(Part::B, "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string()),
-            "29"
+            Day6::default().solve(
+                Part::B,
+                vec!["nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string()]
+            ),
+            Ok(Answer::Integer(29))
         );
         assert_eq!(
-            solve(Part::B, "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string()),
-            "26"
+            Day6::default().solve(
+                Part::B,
+                vec!["zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string()]
+            ),
+            Ok(Answer::Integer(26))
         );
     }
 
+    #[test]
+    fn it_uses_the_configured_window_for_both_parts() {
+        let day = Day6 {
+            window: NonZeroUsize::new(14),
+        };
+        let lines = vec!["mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()];
+
+        assert_eq!(day.solve(Part::A, lines.clone()), Ok(Answer::Integer(19)));
+        assert_eq!(day.solve(Part::B, lines), Ok(Answer::Integer(19)));
+    }
+
+    #[test]
+    fn it_fails_without_a_marker() {
+        assert!(Day6::default()
+            .solve(Part::A, vec!["abcabc".to_string()])
+            .is_err());
+        assert!(Day6::default()
+            .solve(Part::B, vec!["abcdabc".to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn it_refuses_windows_longer_than_the_byte_values() {
+        let day = Day6 {
+            window: NonZeroUsize::new(100_000_000_000),
+        };
+        let lines = vec!["mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()];
+
+        assert!(day.solve(Part::A, lines.clone()).is_err());
+        assert!(Puzzle::report(&day, lines).is_err());
+    }
+
     #[test]
     fn test_find_marker_with_example_1() {
-        assert_eq!(find_marker("bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()), 5);
+        assert_eq!(
+            first_marker("bvwbjplbgvbhsrlpgdmjqwftvncz".bytes(), PACKET),
+            Some(5)
+        );
     }
 
     #[test]
     fn test_find_marker_with_example_2() {
-        assert_eq!(find_marker("nppdvjthqldpwncqszvftbrmjlhg".to_string()), 6);
+        assert_eq!(
+            first_marker("nppdvjthqldpwncqszvftbrmjlhg".bytes(), PACKET),
+            Some(6)
+        );
     }
 
     #[test]
     fn test_find_marker_with_example_3() {
         assert_eq!(
-            find_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string()),
-            10
+            first_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".bytes(), PACKET),
+            Some(10)
         );
     }
 
     #[test]
     fn test_find_marker_with_example_4() {
         assert_eq!(
-            find_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string()),
-            11
+            first_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".bytes(), PACKET),
+            Some(11)
         );
     }
 
     #[test]
     fn test_find_generic_marker_with_example_1() {
         assert_eq!(
-            find_generic_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string(), 14),
-            19
+            first_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb".bytes(), MESSAGE),
+            Some(19)
         );
     }
 
     #[test]
     fn test_find_generic_marker_with_example_2() {
         assert_eq!(
-            find_generic_marker("bvwbjplbgvbhsrlpgdmjqwftvncz".to_string(), 14),
-            23
+            first_marker("bvwbjplbgvbhsrlpgdmjqwftvncz".bytes(), MESSAGE),
+            Some(23)
         );
     }
 
     #[test]
     fn test_find_generic_marker_with_example_3() {
         assert_eq!(
-            find_generic_marker("nppdvjthqldpwncqszvftbrmjlhg".to_string(), 14),
-            23
+            first_marker("nppdvjthqldpwncqszvftbrmjlhg".bytes(), MESSAGE),
+            Some(23)
         );
     }
 
     #[test]
     fn test_find_generic_marker_with_example_4() {
         assert_eq!(
-            find_generic_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string(), 14),
-            29
+            first_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".bytes(), MESSAGE),
+            Some(29)
         );
     }
 
     #[test]
     fn test_find_generic_marker_with_example_5() {
         assert_eq!(
-            find_generic_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string(), 14),
-            26
+            first_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".bytes(), MESSAGE),
+            Some(26)
         );
     }
 }
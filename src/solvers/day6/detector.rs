@@ -0,0 +1,147 @@
+use std::io::{self, BufRead, BufReader, Read};
+
+/// Number of distinct byte values, so the longest window that can hold distinct bytes only.
+pub const BYTE_VALUES: usize = 256;
+
+/// Watches a stream of bytes for a window of distinct ones, one byte at a time.
+///
+/// Only the last `window` bytes and a count of each byte value inside them are kept, so every
+/// byte costs the same whatever the window length and however long the stream is.
+pub struct Detector {
+    window: usize,
+    recent: Vec<u8>,
+    counts: [usize; BYTE_VALUES],
+    distinct: usize,
+    position: usize,
+}
+
+impl Detector {
+    pub fn new(window: usize) -> Detector {
+        assert!(window > 0, "a marker is at least one byte long");
+        assert!(
+            window <= BYTE_VALUES,
+            "a marker has at most one of each byte"
+        );
+        Detector {
+            window,
+            recent: vec![0; window],
+            counts: [0; BYTE_VALUES],
+            distinct: 0,
+            position: 0,
+        }
+    }
+
+    /// Feeds the next byte, returning true if it ends `window` distinct bytes.
+    pub fn push(&mut self, byte: u8) -> bool {
+        let slot = self.position % self.window;
+        if self.position >= self.window {
+            let old = self.recent[slot] as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 0 {
+                self.distinct -= 1;
+            }
+        }
+        self.recent[slot] = byte;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+        self.position += 1;
+        self.distinct == self.window
+    }
+
+    /// Number of bytes fed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// Number of bytes read when the first window of `window` distinct bytes is complete.
+pub fn first_marker(bytes: impl IntoIterator<Item = u8>, window: usize) -> Option<usize> {
+    markers(bytes, window).next()
+}
+
+/// Every position where a window of `window` distinct bytes ends, counted in bytes read.
+pub fn markers<I: IntoIterator<Item = u8>>(bytes: I, window: usize) -> Markers<I::IntoIter> {
+    Markers {
+        bytes: bytes.into_iter(),
+        detector: Detector::new(window),
+    }
+}
+
+pub struct Markers<I> {
+    bytes: I,
+    detector: Detector,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Markers<I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let detector = &mut self.detector;
+        self.bytes
+            .find(|byte| detector.push(*byte))
+            .map(|_| detector.position())
+    }
+}
+
+/// Like [`first_marker`] on the first line of `reader`, read in buffered chunks and only up to
+/// the marker, so that the signal never has to fit in memory.
+pub fn read_marker(reader: impl Read, window: usize) -> io::Result<Option<usize>> {
+    let mut reader = BufReader::new(reader);
+    let mut detector = Detector::new(window);
+    loop {
+        let chunk = reader.fill_buf()?;
+        for byte in chunk {
+            if matches!(byte, b'\n' | b'\r') {
+                return Ok(None);
+            }
+            if detector.push(*byte) {
+                return Ok(Some(detector.position()));
+            }
+        }
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+        let length = chunk.len();
+        reader.consume(length);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::day6::detector::{first_marker, markers, read_marker, Detector};
+
+    #[test]
+    fn it_tracks_the_distinct_bytes_of_the_window() {
+        let mut detector = Detector::new(3);
+        let found: Vec<bool> = b"aabcb".iter().map(|b| detector.push(*b)).collect();
+
+        assert_eq!(found, vec![false, false, false, true, false]);
+        assert_eq!(detector.position(), 5);
+    }
+
+    #[test]
+    fn it_finds_a_marker_ending_the_stream() {
+        assert_eq!(first_marker(b"aaab".iter().copied(), 2), Some(4));
+        assert_eq!(first_marker(b"aaaa".iter().copied(), 2), None);
+    }
+
+    #[test]
+    fn it_lists_every_marker() {
+        let positions: Vec<usize> = markers(b"abcabbcd".iter().copied(), 3).collect();
+
+        assert_eq!(positions, vec![3, 4, 5, 8]);
+        assert_eq!(markers(b"aaaa".iter().copied(), 2).count(), 0);
+    }
+
+    #[test]
+    fn it_reads_the_marker_from_a_reader() {
+        let signal = "mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes();
+
+        assert_eq!(read_marker(signal, 4).unwrap(), Some(7));
+        assert_eq!(read_marker(signal, 14).unwrap(), Some(19));
+        assert_eq!(read_marker(signal, 27).unwrap(), None);
+        assert_eq!(read_marker("abc\ndefg".as_bytes(), 4).unwrap(), None);
+    }
+}
@@ -1,69 +1,126 @@
-use crate::Part;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::solvers::Solver;
+use clap::ValueEnum;
+use fs_tree::FsTree;
+pub use planner::Disk;
+pub use render::{SortBy, TreeView};
+use replay::{replay, Replay};
 
-pub fn solve(part: Part, lines: Vec<String>) -> String {
-    let result = match part {
-        Part::A => {
-            let dirs = get_dirs(lines);
-            dirs.into_values()
-                .filter(|size| *size <= 100_000)
-                .sum::<u32>()
-        }
-        Part::B => {
-            let dirs = get_dirs(lines);
-            let disk = 70_000_000;
-            let needed = 30_000_000;
-            let root = dirs.get(&PathBuf::from("/")).unwrap();
-            let available = disk - root;
+mod fs_tree;
+mod planner;
+mod render;
+mod replay;
 
-            dirs.into_values()
-                .filter(|size| available + size >= needed)
-                .min()
-                .unwrap()
-        }
-    };
-    format!("{}", result)
+#[derive(Default)]
+pub struct Day7 {
+    pub disk: Disk,
+    pub show: Show,
+    /// How `report` draws the filesystem.
+    pub view: TreeView,
 }
 
-fn get_dirs(lines: Vec<String>) -> HashMap<PathBuf, u32> {
-    let mut sizes = HashMap::new();
-    let mut affected = Vec::new();
+/// What the report of day 7 is about.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Show {
+    /// The filesystem, drawn like `tree`
+    #[default]
+    Tree,
+    /// Every directory that frees enough space on its own
+    Candidates,
+    /// The directories to delete to free enough space while deleting as little as possible
+    Plan,
+}
 
-    for line in lines {
-        if line.starts_with("$ ls") || line.starts_with("dir") {
-            continue;
-        }
+impl Solver for Day7 {
+    type Input = Replay;
 
-        let parts: Vec<_> = line.split_whitespace().collect();
-        match parts[..] {
-            ["$", "cd", ".."] => {
-                affected.pop();
-            }
-            ["$", "cd", name] => {
-                affected.push(String::from(name));
+    fn parse(&self, lines: Vec<String>) -> Result<Replay, ParseError> {
+        replay(lines)
+    }
+
+    fn part_a(&self, Replay { tree, .. }: &Replay) -> Result<Answer, SolveError> {
+        let sizes = tree.sizes();
+        let result = tree
+            .dirs()
+            .map(|dir| sizes[dir])
+            .filter(|size| *size <= 100_000)
+            .sum::<u64>();
+        Ok(Answer::from(result))
+    }
+
+    fn part_b(&self, Replay { tree, .. }: &Replay) -> Result<Answer, SolveError> {
+        let sizes = tree.sizes();
+        let shortfall = self.shortfall(&sizes)?;
+        let smallest = planner::candidates(tree, &sizes, shortfall)
+            .into_iter()
+            .next()
+            .ok_or_else(|| SolveError::no_answer("no directory frees enough space"))?;
+        Ok(Answer::from(smallest.frees))
+    }
+
+    fn warnings(&self, replay: &Replay) -> Vec<ParseError> {
+        replay.warnings.clone()
+    }
+
+    fn report(&self, Replay { tree, .. }: &Replay) -> Result<Option<String>, SolveError> {
+        let report = match self.show {
+            Show::Tree => render::render(tree, &self.view),
+            Show::Candidates => {
+                let sizes = tree.sizes();
+                let shortfall = self.shortfall(&sizes)?;
+                let mut report = format!("{} more bytes must be freed\n", shortfall);
+                for candidate in planner::candidates(tree, &sizes, shortfall) {
+                    report.push_str(&format!(
+                        "{:>10}  {}\n",
+                        candidate.frees,
+                        tree.path(candidate.dir)
+                    ));
+                }
+                report
             }
-            [size, _name] => {
-                let size: u32 = size.parse().unwrap();
-                for idx in 0..affected.len() {
-                    let path = PathBuf::from_iter(&affected[..=idx]);
-                    *sizes.entry(path).or_insert(0) += size;
+            Show::Plan => {
+                let sizes = tree.sizes();
+                let shortfall = self.shortfall(&sizes)?;
+                let plan = planner::plan(tree, &sizes, shortfall).ok_or_else(|| {
+                    SolveError::no_answer("deleting everything does not free enough space")
+                })?;
+                let mut report = format!(
+                    "deleting {} directories frees {} of the {} missing bytes\n",
+                    plan.dirs.len(),
+                    plan.frees,
+                    shortfall
+                );
+                for dir in plan.dirs {
+                    report.push_str(&format!("{:>10}  {}\n", sizes[dir], tree.path(dir)));
                 }
+                report
             }
-            _ => {}
         };
+        Ok(Some(report))
+    }
+}
+
+impl Day7 {
+    /// Bytes to delete before the update fits on the disk.
+    fn shortfall(&self, sizes: &[u64]) -> Result<u64, SolveError> {
+        let used = sizes[FsTree::ROOT];
+        self.disk.shortfall(used).ok_or_else(|| {
+            SolveError::no_answer(format!(
+                "{} bytes do not fit on a disk of {}",
+                used, self.disk.capacity
+            ))
+        })
     }
-    sizes
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solvers::day7::{get_dirs, solve};
+    use crate::answer::Answer;
+    use crate::solvers::day7::{replay, Day7, FsTree};
+    use crate::solvers::Puzzle;
     use crate::Part;
     use std::collections::HashMap;
-    use std::iter::Map;
-    use std::path::PathBuf;
-    use std::vec::Drain;
 
     #[test]
     fn solve_examples_part_a() {
@@ -92,8 +149,8 @@ mod tests {
             "5626152 d.ext".to_string(),
             "7214296 k".to_string(),
         ];
-        let result = solve(Part::A, lines);
-        assert_eq!(result, "95437");
+        let result = Day7::default().solve(Part::A, lines);
+        assert_eq!(result, Ok(Answer::Integer(95437)));
     }
 
     #[test]
@@ -106,10 +163,12 @@ mod tests {
             "$ cd a".to_string(),
             "100 c.txt".to_string(),
         ];
-        let dirs = get_dirs(lines);
+        let tree = replay(lines).unwrap().tree;
+        let sizes = tree.sizes();
+        let dirs: HashMap<usize, u64> = tree.dirs().map(|d| (d, sizes[d])).collect();
         let mut expected = HashMap::new();
-        expected.insert(PathBuf::from("/"), 200);
-        expected.insert(PathBuf::from("/a"), 100);
+        expected.insert(FsTree::ROOT, 200);
+        expected.insert(tree.child(FsTree::ROOT, "a").unwrap(), 100);
 
         assert_eq!(dirs, expected);
     }
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+pub type NodeId = usize;
+
+/// A filesystem rebuilt from a terminal transcript. Nodes live in an arena and refer to each
+/// other by index; a node is always created after its parent, so children have higher ids.
+#[derive(Debug, PartialEq)]
+pub struct FsTree {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: NodeKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NodeKind {
+    Dir {
+        children: Vec<NodeId>,
+        /// The same children by name, so that replaying a long listing stays linear.
+        by_name: HashMap<String, NodeId>,
+    },
+    File {
+        size: u64,
+    },
+}
+
+impl NodeKind {
+    fn empty_dir() -> NodeKind {
+        NodeKind::Dir {
+            children: Vec::new(),
+            by_name: HashMap::new(),
+        }
+    }
+}
+
+impl FsTree {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> FsTree {
+        FsTree {
+            nodes: vec![Node {
+                name: String::from("/"),
+                parent: None,
+                kind: NodeKind::empty_dir(),
+            }],
+        }
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.nodes[id].kind {
+            NodeKind::Dir { children, .. } => children,
+            NodeKind::File { .. } => &[],
+        }
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        match &self.nodes[id].kind {
+            NodeKind::Dir { by_name, .. } => by_name.get(name).copied(),
+            NodeKind::File { .. } => None,
+        }
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.nodes[id].name
+    }
+
+    pub fn kind(&self, id: NodeId) -> &NodeKind {
+        &self.nodes[id].kind
+    }
+
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Dir { .. })
+    }
+
+    /// Every directory, root first.
+    pub fn dirs(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|id| self.is_dir(*id))
+    }
+
+    /// Returns the directory `name` inside `parent`, creating it if needed.
+    pub fn add_dir(&mut self, parent: NodeId, name: &str) -> NodeId {
+        match self.child(parent, name) {
+            Some(existing) => existing,
+            None => self.push(parent, name, NodeKind::empty_dir()),
+        }
+    }
+
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: u64) -> NodeId {
+        self.push(parent, name, NodeKind::File { size })
+    }
+
+    /// Changes the size of a file, leaving directories alone.
+    pub fn set_size(&mut self, id: NodeId, size: u64) {
+        if let NodeKind::File { size: current } = &mut self.nodes[id].kind {
+            *current = size;
+        }
+    }
+
+    fn push(&mut self, parent: NodeId, name: &str, kind: NodeKind) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+        });
+        if let NodeKind::Dir { children, by_name } = &mut self.nodes[parent].kind {
+            children.push(id);
+            by_name.entry(name.to_string()).or_insert(id);
+        }
+        id
+    }
+
+    /// Size of every node indexed by id, directories counting everything below them.
+    pub fn sizes(&self) -> Vec<u64> {
+        let mut sizes: Vec<u64> = self
+            .nodes
+            .iter()
+            .map(|node| match node.kind {
+                NodeKind::File { size } => size,
+                NodeKind::Dir { .. } => 0,
+            })
+            .collect();
+        for id in (1..self.nodes.len()).rev() {
+            if let Some(parent) = self.nodes[id].parent {
+                sizes[parent] += sizes[id];
+            }
+        }
+        sizes
+    }
+
+    /// Absolute path of a node, like `/a/e`.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.nodes[current].parent {
+            names.push(self.nodes[current].name.as_str());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+}
+
+impl Default for FsTree {
+    fn default() -> FsTree {
+        FsTree::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::day7::fs_tree::FsTree;
+
+    #[test]
+    fn it_links_nodes_to_their_parent() {
+        let mut tree = FsTree::new();
+        let a = tree.add_dir(FsTree::ROOT, "a");
+        let e = tree.add_dir(a, "e");
+        let i = tree.add_file(e, "i", 584);
+
+        assert_eq!(tree.add_dir(FsTree::ROOT, "a"), a);
+        assert_eq!(tree.parent(i), Some(e));
+        assert_eq!(tree.child(a, "e"), Some(e));
+        assert!(!tree.is_dir(i));
+        assert_eq!(tree.path(i), "/a/e/i");
+        assert_eq!(tree.path(FsTree::ROOT), "/");
+        assert_eq!(tree.dirs().collect::<Vec<_>>(), vec![FsTree::ROOT, a, e]);
+    }
+
+    #[test]
+    fn it_adds_up_sizes_of_directories() {
+        let mut tree = FsTree::new();
+        let a = tree.add_dir(FsTree::ROOT, "a");
+        tree.add_file(FsTree::ROOT, "b.txt", 100);
+        tree.add_file(a, "c.txt", 20);
+        tree.add_file(a, "d.txt", 3);
+
+        assert_eq!(tree.sizes(), vec![123, 23, 100, 20, 3]);
+
+        tree.set_size(4, 30);
+        assert_eq!(tree.sizes()[FsTree::ROOT], 150);
+    }
+}
@@ -0,0 +1,422 @@
+use crate::solvers::day7::fs_tree::{FsTree, NodeId};
+
+/// The disk the filesystem lives on, and how much of it must be free for the update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Disk {
+    pub capacity: u64,
+    pub needed: u64,
+}
+
+impl Default for Disk {
+    fn default() -> Disk {
+        Disk {
+            capacity: 70_000_000,
+            needed: 30_000_000,
+        }
+    }
+}
+
+impl Disk {
+    /// Bytes that still have to be deleted, or `None` if the filesystem does not fit on the disk.
+    pub fn shortfall(&self, used: u64) -> Option<u64> {
+        let available = self.capacity.checked_sub(used)?;
+        Some(self.needed.saturating_sub(available))
+    }
+}
+
+/// A directory that frees enough space on its own.
+#[derive(Debug, PartialEq)]
+pub struct Candidate {
+    pub dir: NodeId,
+    pub frees: u64,
+}
+
+/// Every directory whose deletion alone frees `shortfall` bytes, smallest first.
+pub fn candidates(tree: &FsTree, sizes: &[u64], shortfall: u64) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = tree
+        .dirs()
+        .filter(|dir| sizes[*dir] >= shortfall)
+        .map(|dir| Candidate {
+            dir,
+            frees: sizes[dir],
+        })
+        .collect();
+    candidates.sort_by_key(|c| (c.frees, c.dir));
+    candidates
+}
+
+/// Directories to delete together, none of them inside another.
+#[derive(Debug, PartialEq)]
+pub struct Plan {
+    pub dirs: Vec<NodeId>,
+    pub frees: u64,
+}
+
+/// Finds the directories that free at least `shortfall` bytes while deleting as little as
+/// possible.
+///
+/// Taking the directories in depth-first order, each one is either deleted, skipping everything
+/// inside it, or kept. The totals that the remaining directories can free are tracked from the
+/// last one backwards, each set only kept until no directory before it needs it any more. No plan
+/// frees more than the smallest single candidate, so the sets stop there. Each directory also
+/// records the totals it is the first to reach, which is enough to retrace the plan afterwards.
+pub fn plan(tree: &FsTree, sizes: &[u64], shortfall: u64) -> Option<Plan> {
+    let limit = candidates(tree, sizes, shortfall).first()?.frees;
+    let (order, ends) = preorder(tree);
+
+    // number of directories still to come whose deletion continues from each position
+    let mut wanted = vec![0; order.len() + 1];
+    for end in &ends {
+        wanted[*end] += 1;
+    }
+    let mut reachable = Sums::listed(limit, vec![0]);
+    let mut kept: Vec<Option<Sums>> = vec![None; order.len() + 1];
+    kept[order.len()] = Some(reachable.clone());
+    let mut added = Vec::with_capacity(order.len());
+    for i in (0..order.len()).rev() {
+        let end = ends[i];
+        let (next, new) = reachable.with_shifted(kept[end].as_ref()?, sizes[order[i]]);
+        wanted[end] -= 1;
+        if wanted[end] == 0 {
+            kept[end] = None;
+        }
+        reachable = next;
+        added.push(new);
+        if wanted[i] > 0 {
+            kept[i] = Some(reachable.clone());
+        }
+    }
+    added.reverse();
+
+    let frees = reachable.first_from(shortfall)?;
+    let mut dirs = Vec::new();
+    let (mut i, mut left) = (0, frees);
+    while left > 0 {
+        // only deleting the directory reaches a total it is the first to reach
+        if added[i].contains(left) {
+            dirs.push(order[i]);
+            left -= sizes[order[i]];
+            i = ends[i];
+        } else {
+            i += 1;
+        }
+    }
+    Some(Plan { dirs, frees })
+}
+
+/// Lists the directories depth-first, along with the position after each one's subtree in that
+/// list. The walk keeps its own stack, as a transcript can nest directories far deeper than the
+/// call stack allows.
+fn preorder(tree: &FsTree) -> (Vec<NodeId>, Vec<usize>) {
+    enum Visit {
+        Enter(NodeId),
+        /// Every directory below the one at this position has been listed.
+        Leave(usize),
+    }
+
+    let (mut order, mut ends) = (Vec::new(), Vec::new());
+    let mut stack = vec![Visit::Enter(FsTree::ROOT)];
+    while let Some(visit) = stack.pop() {
+        match visit {
+            Visit::Enter(dir) => {
+                stack.push(Visit::Leave(order.len()));
+                order.push(dir);
+                ends.push(0);
+                let children = tree.children(dir).iter().rev();
+                stack.extend(
+                    children
+                        .filter(|c| tree.is_dir(**c))
+                        .map(|c| Visit::Enter(*c)),
+                );
+            }
+            Visit::Leave(position) => ends[position] = order.len(),
+        }
+    }
+    (order, ends)
+}
+
+/// A set of totals from 0 to a limit.
+///
+/// The totals are listed while they are few, and turn into one bit per total between the
+/// smallest and the largest once that takes less memory. A set thus never takes more than a bit
+/// per byte of the limit, and far less when the sizes leave few totals reachable or only reach
+/// new totals in a narrow range.
+#[derive(Clone)]
+struct Sums {
+    limit: u64,
+    totals: Totals,
+}
+
+#[derive(Clone)]
+enum Totals {
+    /// Sorted, without duplicates.
+    Listed(Vec<u64>),
+    /// One bit per total from the `offset`-th word of 64 totals on.
+    Bits { offset: usize, words: Vec<u64> },
+}
+
+impl Sums {
+    fn listed(limit: u64, mut totals: Vec<u64>) -> Sums {
+        let span = match (totals.first(), totals.last()) {
+            (Some(first), Some(last)) => (last / 64 - first / 64) as usize + 1,
+            _ => 0,
+        };
+        if totals.len() > span {
+            let mut words = vec![0; Sums::words(limit)];
+            for total in totals {
+                words[(total / 64) as usize] |= 1 << (total % 64);
+            }
+            return Sums::bits(limit, words);
+        }
+        totals.shrink_to_fit();
+        Sums {
+            limit,
+            totals: Totals::Listed(totals),
+        }
+    }
+
+    /// The totals set in `words`, a bit per total from 0 to the limit.
+    fn bits(limit: u64, mut words: Vec<u64>) -> Sums {
+        let end = words
+            .iter()
+            .rposition(|word| *word != 0)
+            .map_or(0, |last| last + 1);
+        let offset = words[..end]
+            .iter()
+            .position(|word| *word != 0)
+            .unwrap_or(end);
+        let count: usize = words[offset..end]
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum();
+        if count <= end - offset {
+            return Sums {
+                limit,
+                totals: Totals::Listed(set_bits(&words[offset..end], offset).collect()),
+            };
+        }
+        words.truncate(end);
+        words.drain(..offset);
+        words.shrink_to_fit();
+        Sums {
+            limit,
+            totals: Totals::Bits { offset, words },
+        }
+    }
+
+    /// Number of words of a bit per total up to `limit`.
+    fn words(limit: u64) -> usize {
+        (limit / 64 + 1) as usize
+    }
+
+    fn contains(&self, total: u64) -> bool {
+        match &self.totals {
+            Totals::Listed(totals) => totals.binary_search(&total).is_ok(),
+            Totals::Bits { offset, words } => (total / 64)
+                .checked_sub(*offset as u64)
+                .and_then(|index| words.get(index as usize))
+                .is_some_and(|word| word & (1 << (total % 64)) != 0),
+        }
+    }
+
+    /// The smallest total from `start` on.
+    fn first_from(&self, start: u64) -> Option<u64> {
+        match &self.totals {
+            Totals::Listed(totals) => totals
+                .get(totals.partition_point(|total| *total < start))
+                .copied(),
+            Totals::Bits { offset, words } => {
+                set_bits(words, *offset).find(|total| *total >= start)
+            }
+        }
+    }
+
+    /// A bit per total from 0 to the limit.
+    fn to_bits(&self) -> Vec<u64> {
+        let mut all = vec![0; Sums::words(self.limit)];
+        match &self.totals {
+            Totals::Listed(totals) => {
+                for total in totals {
+                    all[(total / 64) as usize] |= 1 << (total % 64);
+                }
+            }
+            Totals::Bits { offset, words } => {
+                all[*offset..*offset + words.len()].copy_from_slice(words);
+            }
+        }
+        all
+    }
+
+    /// These totals along with every total of `other` increased by `shift`, and apart the ones
+    /// that were not already among these.
+    fn with_shifted(&self, other: &Sums, shift: u64) -> (Sums, Sums) {
+        let limit = self.limit;
+        if let (Totals::Listed(own), Totals::Listed(theirs)) = (&self.totals, &other.totals) {
+            if own.len() + theirs.len() <= Sums::words(limit) {
+                let (merged, new) = merge_shifted(own, theirs, shift, limit);
+                return (Sums::listed(limit, merged), Sums::listed(limit, new));
+            }
+        }
+
+        let before = self.to_bits();
+        let mut words = before.clone();
+        match &other.totals {
+            Totals::Listed(theirs) => {
+                for total in theirs.iter().map(|t| t + shift).take_while(|t| *t <= limit) {
+                    words[(total / 64) as usize] |= 1 << (total % 64);
+                }
+            }
+            Totals::Bits { .. } if shift <= limit => {
+                let theirs = other.to_bits();
+                let (offset, bits) = ((shift / 64) as usize, shift % 64);
+                for (target, word) in words.iter_mut().enumerate().skip(offset) {
+                    let source = target - offset;
+                    *word |= theirs[source] << bits;
+                    if bits > 0 && source > 0 {
+                        *word |= theirs[source - 1] >> (64 - bits);
+                    }
+                }
+                // the shift may carry bits past the limit into the last word
+                if let Some(last) = words.last_mut() {
+                    let used = limit % 64 + 1;
+                    if used < 64 {
+                        *last &= (1 << used) - 1;
+                    }
+                }
+            }
+            Totals::Bits { .. } => {}
+        }
+        let new = words.iter().zip(&before).map(|(w, b)| w & !b).collect();
+        (Sums::bits(limit, words), Sums::bits(limit, new))
+    }
+}
+
+/// The totals of `own` and of `theirs` increased by `shift` up to `limit`, both sorted, and apart
+/// the ones only `theirs` brings.
+fn merge_shifted(own: &[u64], theirs: &[u64], shift: u64, limit: u64) -> (Vec<u64>, Vec<u64>) {
+    let mut own = own.iter().copied().peekable();
+    let mut shifted = theirs
+        .iter()
+        .map(|total| total + shift)
+        .take_while(|total| *total <= limit)
+        .peekable();
+    let (mut merged, mut new) = (Vec::new(), Vec::new());
+    loop {
+        match (own.peek().copied(), shifted.peek().copied()) {
+            (Some(a), Some(b)) if a <= b => {
+                merged.push(a);
+                own.next();
+                if a == b {
+                    shifted.next();
+                }
+            }
+            (_, Some(b)) => {
+                merged.push(b);
+                new.push(b);
+                shifted.next();
+            }
+            (Some(a), None) => {
+                merged.push(a);
+                own.next();
+            }
+            (None, None) => return (merged, new),
+        }
+    }
+}
+
+/// The totals whose bit is set in `words`, which start at the `offset`-th word, in order.
+fn set_bits(words: &[u64], offset: usize) -> impl Iterator<Item = u64> + '_ {
+    words.iter().enumerate().flat_map(move |(index, word)| {
+        let mut rest = *word;
+        std::iter::from_fn(move || {
+            let bit = (rest != 0).then(|| rest.trailing_zeros())?;
+            rest &= rest - 1;
+            Some((offset + index) as u64 * 64 + u64::from(bit))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::day7::fs_tree::FsTree;
+    use crate::solvers::day7::planner::{candidates, plan, Candidate, Disk, Plan};
+
+    #[test]
+    fn it_computes_the_missing_space() {
+        let disk = Disk::default();
+
+        assert_eq!(disk.shortfall(48381165), Some(8381165));
+        assert_eq!(disk.shortfall(1000), Some(0));
+        assert_eq!(disk.shortfall(80_000_000), None);
+    }
+
+    #[test]
+    fn it_combines_sibling_directories_when_that_deletes_less() {
+        let mut tree = FsTree::new();
+        let a = tree.add_dir(FsTree::ROOT, "a");
+        let b = tree.add_dir(FsTree::ROOT, "b");
+        let c = tree.add_dir(a, "c");
+        tree.add_file(a, "big", 50);
+        tree.add_file(c, "x", 30);
+        tree.add_file(b, "y", 40);
+        let sizes = tree.sizes();
+
+        assert_eq!(
+            candidates(&tree, &sizes, 60),
+            vec![
+                Candidate { dir: a, frees: 80 },
+                Candidate {
+                    dir: FsTree::ROOT,
+                    frees: 120
+                }
+            ]
+        );
+        assert_eq!(
+            plan(&tree, &sizes, 60),
+            Some(Plan {
+                dirs: vec![c, b],
+                frees: 70
+            })
+        );
+        assert_eq!(plan(&tree, &sizes, 121), None);
+    }
+
+    #[test]
+    fn it_plans_for_deeply_nested_directories() {
+        let mut tree = FsTree::new();
+        let mut dir = FsTree::ROOT;
+        for _ in 0..200_000 {
+            dir = tree.add_dir(dir, "a");
+        }
+        tree.add_file(dir, "x", 10);
+        let sizes = tree.sizes();
+
+        assert_eq!(
+            plan(&tree, &sizes, 5),
+            Some(Plan {
+                dirs: vec![dir],
+                frees: 10
+            })
+        );
+    }
+
+    #[test]
+    fn it_plans_for_terabytes_without_a_bit_per_byte() {
+        let mut tree = FsTree::new();
+        let a = tree.add_dir(FsTree::ROOT, "a");
+        let b = tree.add_dir(FsTree::ROOT, "b");
+        let c = tree.add_dir(a, "c");
+        tree.add_file(a, "big", 5_000_000_000_000);
+        tree.add_file(c, "x", 3_000_000_000_000);
+        tree.add_file(b, "y", 4_000_000_000_000);
+        let sizes = tree.sizes();
+
+        assert_eq!(
+            plan(&tree, &sizes, 6_000_000_000_000),
+            Some(Plan {
+                dirs: vec![c, b],
+                frees: 7_000_000_000_000
+            })
+        );
+    }
+}
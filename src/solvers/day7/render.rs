@@ -0,0 +1,145 @@
+use crate::solvers::day7::fs_tree::{FsTree, NodeId};
+use clap::ValueEnum;
+
+/// Order of the entries of a directory when drawing the filesystem.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum SortBy {
+    /// The order in which `ls` listed them
+    #[default]
+    Listing,
+    /// Alphabetically
+    Name,
+    /// Largest first
+    Size,
+}
+
+/// Which part of the filesystem to draw, like the options of `tree` and `du`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeView {
+    /// Deepest level drawn below `/`, everything when unset.
+    pub max_depth: Option<usize>,
+    /// Entries smaller than this are left out.
+    pub min_size: u64,
+    pub sort: SortBy,
+}
+
+/// Draws the filesystem like `tree`, with the cumulative size of every entry in front of it.
+///
+/// ```text
+/// 48381165  /
+///    94853  ├── a/
+///      584  │   └── e/
+/// ```
+pub fn render(tree: &FsTree, view: &TreeView) -> String {
+    let sizes = tree.sizes();
+    let mut renderer = Renderer {
+        tree,
+        view,
+        width: sizes[FsTree::ROOT].to_string().len(),
+        sizes,
+        out: String::new(),
+    };
+    renderer.line(FsTree::ROOT, "");
+    renderer.children(FsTree::ROOT, 1, "");
+    renderer.out
+}
+
+struct Renderer<'a> {
+    tree: &'a FsTree,
+    view: &'a TreeView,
+    sizes: Vec<u64>,
+    width: usize,
+    out: String,
+}
+
+impl Renderer<'_> {
+    fn line(&mut self, id: NodeId, prefix: &str) {
+        let name = match (id, self.tree.is_dir(id)) {
+            (FsTree::ROOT, _) => "/".to_string(),
+            (_, true) => format!("{}/", self.tree.name(id)),
+            (_, false) => self.tree.name(id).to_string(),
+        };
+        self.out.push_str(&format!(
+            "{:>width$}  {}{}\n",
+            self.sizes[id],
+            prefix,
+            name,
+            width = self.width
+        ));
+    }
+
+    fn children(&mut self, dir: NodeId, depth: usize, indent: &str) {
+        if self.view.max_depth.is_some_and(|max| depth > max) {
+            return;
+        }
+        let mut children: Vec<NodeId> = self
+            .tree
+            .children(dir)
+            .iter()
+            .copied()
+            .filter(|child| self.sizes[*child] >= self.view.min_size)
+            .collect();
+        match self.view.sort {
+            SortBy::Listing => {}
+            SortBy::Name => children.sort_by_key(|child| self.tree.name(*child)),
+            SortBy::Size => children.sort_by_key(|child| std::cmp::Reverse(self.sizes[*child])),
+        }
+
+        for (index, child) in children.iter().enumerate() {
+            let last = index + 1 == children.len();
+            let (branch, nested) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            self.line(*child, &format!("{}{}", indent, branch));
+            self.children(*child, depth + 1, &format!("{}{}", indent, nested));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::day7::fs_tree::FsTree;
+    use crate::solvers::day7::render::{render, SortBy, TreeView};
+
+    fn example() -> FsTree {
+        let mut tree = FsTree::new();
+        let a = tree.add_dir(FsTree::ROOT, "a");
+        let e = tree.add_dir(a, "e");
+        tree.add_file(e, "i", 584);
+        tree.add_file(a, "f", 29116);
+        tree.add_file(FsTree::ROOT, "b.txt", 14848514);
+        tree
+    }
+
+    #[test]
+    fn it_draws_the_whole_tree_in_listing_order() {
+        assert_eq!(
+            render(&example(), &TreeView::default()),
+            "14878214  /\n\
+             \x20  29700  ├── a/\n\
+             \x20    584  │   ├── e/\n\
+             \x20    584  │   │   └── i\n\
+             \x20  29116  │   └── f\n\
+             14848514  └── b.txt\n"
+        );
+    }
+
+    #[test]
+    fn it_sorts_limits_depth_and_hides_small_entries() {
+        let view = TreeView {
+            max_depth: Some(2),
+            min_size: 1000,
+            sort: SortBy::Size,
+        };
+
+        assert_eq!(
+            render(&example(), &view),
+            "14878214  /\n\
+             14848514  ├── b.txt\n\
+             \x20  29700  └── a/\n\
+             \x20  29116      └── f\n"
+        );
+    }
+}
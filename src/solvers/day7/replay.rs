@@ -0,0 +1,205 @@
+use crate::error::ParseError;
+use crate::solvers::day7::fs_tree::{FsTree, NodeId, NodeKind};
+
+/// The filesystem seen by a transcript, with the listings that contradicted earlier ones.
+#[derive(Debug, PartialEq)]
+pub struct Replay {
+    pub tree: FsTree,
+    pub warnings: Vec<ParseError>,
+}
+
+/// Rebuilds the filesystem by replaying the `cd` and `ls` commands of a transcript.
+///
+/// Replaying is idempotent: listing a directory again does not count its files twice. `cd` takes
+/// absolute and relative paths, and `cd ..` stays in `/` like a shell does.
+pub fn replay(lines: Vec<String>) -> Result<Replay, ParseError> {
+    let mut tree = FsTree::new();
+    let mut warnings = Vec::new();
+    let mut cwd = FsTree::ROOT;
+
+    for (index, line) in lines.into_iter().enumerate() {
+        let parts: Vec<_> = line.split_whitespace().collect();
+        match parts[..] {
+            ["$", "ls"] => {}
+            ["$", "cd", path] => {
+                cwd = change_dir(&mut tree, cwd, path)
+                    .map_err(|message| ParseError::at(&line, path, message).on_line(index))?;
+            }
+            ["dir", name] => match tree.child(cwd, name) {
+                Some(existing) if !tree.is_dir(existing) => warnings.push(
+                    ParseError::at(&line, name, "listed as a directory, but was a file before")
+                        .on_line(index),
+                ),
+                _ => {
+                    tree.add_dir(cwd, name);
+                }
+            },
+            [size, name] => {
+                let size: u64 = size.parse().map_err(|_| {
+                    ParseError::at(&line, size, "expected a file size").on_line(index)
+                })?;
+                if let Some(warning) = list_file(&mut tree, cwd, name, size) {
+                    warnings.push(ParseError::at(&line, name, warning).on_line(index));
+                }
+            }
+            _ => {
+                return Err(
+                    ParseError::line(&line, "expected a command or a directory listing")
+                        .on_line(index),
+                )
+            }
+        };
+    }
+    Ok(Replay { tree, warnings })
+}
+
+fn change_dir(tree: &mut FsTree, cwd: NodeId, path: &str) -> Result<NodeId, String> {
+    let mut dir = if path.starts_with('/') {
+        FsTree::ROOT
+    } else {
+        cwd
+    };
+    for name in path.split('/').filter(|n| !n.is_empty() && *n != ".") {
+        dir = match (name, tree.child(dir, name)) {
+            ("..", _) => tree.parent(dir).unwrap_or(FsTree::ROOT),
+            (_, Some(existing)) if !tree.is_dir(existing) => {
+                return Err(format!(
+                    "{} is a file, not a directory",
+                    tree.path(existing)
+                ))
+            }
+            _ => tree.add_dir(dir, name),
+        };
+    }
+    Ok(dir)
+}
+
+/// Records a file shown by `ls`, returning a warning if it contradicts an earlier listing.
+fn list_file(tree: &mut FsTree, dir: NodeId, name: &str, size: u64) -> Option<String> {
+    let Some(existing) = tree.child(dir, name) else {
+        tree.add_file(dir, name, size);
+        return None;
+    };
+    match tree.kind(existing) {
+        NodeKind::File { size: listed } if *listed == size => None,
+        NodeKind::File { size: listed } => {
+            let warning = format!(
+                "{} was listed with {} bytes before, now with {}",
+                tree.path(existing),
+                listed,
+                size
+            );
+            tree.set_size(existing, size);
+            Some(warning)
+        }
+        NodeKind::Dir { .. } => Some(format!(
+            "{} was listed as a directory before",
+            tree.path(existing)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::day7::fs_tree::FsTree;
+    use crate::solvers::day7::replay::replay;
+
+    fn lines(transcript: &[&str]) -> Vec<String> {
+        transcript.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn it_keeps_names_and_structure_of_the_transcript() {
+        let replay = replay(lines(&[
+            "$ cd /",
+            "$ ls",
+            "dir a",
+            "100 b.txt",
+            "$ cd a",
+            "$ ls",
+            "100 c.txt",
+            "$ cd ..",
+        ]))
+        .unwrap();
+        let tree = replay.tree;
+        let a = tree.child(FsTree::ROOT, "a").unwrap();
+
+        assert!(tree.is_dir(a));
+        assert!(tree.child(FsTree::ROOT, "b.txt").is_some());
+        assert!(tree.child(a, "c.txt").is_some());
+        assert!(replay.warnings.is_empty());
+    }
+
+    #[test]
+    fn it_counts_files_listed_twice_once() {
+        let replay = replay(lines(&[
+            "$ cd /",
+            "$ ls",
+            "100 b.txt",
+            "dir a",
+            "$ ls",
+            "100 b.txt",
+            "dir a",
+        ]))
+        .unwrap();
+
+        assert_eq!(replay.tree.sizes()[FsTree::ROOT], 100);
+        assert_eq!(replay.tree.dirs().count(), 2);
+    }
+
+    #[test]
+    fn it_goes_back_to_root_and_follows_absolute_paths() {
+        let replay = replay(lines(&[
+            "$ cd /",
+            "$ cd a",
+            "$ cd /",
+            "$ cd ..",
+            "$ ls",
+            "10 x",
+            "$ cd /a/b",
+            "$ ls",
+            "20 y",
+            "$ cd ../../a/./b/..",
+            "$ ls",
+            "30 z",
+        ]))
+        .unwrap();
+        let tree = replay.tree;
+        let a = tree.child(FsTree::ROOT, "a").unwrap();
+        let b = tree.child(a, "b").unwrap();
+
+        assert!(tree.child(FsTree::ROOT, "x").is_some());
+        assert!(tree.child(b, "y").is_some());
+        assert!(tree.child(a, "z").is_some());
+        assert_eq!(tree.sizes()[FsTree::ROOT], 60);
+    }
+
+    #[test]
+    fn it_warns_about_inconsistent_listings() {
+        let replay = replay(lines(&[
+            "$ ls",
+            "100 b.txt",
+            "dir c",
+            "$ ls",
+            "120 b.txt",
+            "5 c",
+        ]))
+        .unwrap();
+
+        assert_eq!(replay.tree.sizes()[FsTree::ROOT], 120);
+        assert_eq!(replay.warnings.len(), 2);
+        assert_eq!(
+            replay.warnings[0].message,
+            "/b.txt was listed with 100 bytes before, now with 120"
+        );
+        assert_eq!((replay.warnings[0].line, replay.warnings[0].column), (5, 5));
+    }
+
+    #[test]
+    fn it_rejects_unknown_lines_and_cd_into_files() {
+        let error = replay(lines(&["$ cd /", "$ ls", "1OO b.txt"])).unwrap_err();
+        assert_eq!((error.line, error.column, error.width), (3, 1, 3));
+        assert!(replay(lines(&["$ rm -rf /"])).is_err());
+        assert!(replay(lines(&["$ ls", "1 b", "$ cd b"])).is_err());
+    }
+}